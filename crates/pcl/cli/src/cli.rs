@@ -6,15 +6,20 @@ use pcl_core::{
     DEFAULT_PLATFORM_URL,
     apply::ApplyArgs,
     auth::AuthCommand,
+    cache::CacheArgs,
     config::ConfigArgs,
+    doctor::DoctorArgs,
     download::DownloadArgs,
 };
-use pcl_phoundry::build::BuildArgs;
 #[cfg(feature = "credible")]
 use pcl_phoundry::phorge_test::PhorgeTest;
+use pcl_phoundry::{
+    build::BuildArgs,
+    lint::LintArgs,
+};
 use std::sync::OnceLock;
 
-fn version_message() -> &'static str {
+pub fn version_message() -> &'static str {
     static VERSION: OnceLock<String> = OnceLock::new();
     VERSION
         .get_or_init(|| {
@@ -29,6 +34,16 @@ fn version_message() -> &'static str {
         .as_str()
 }
 
+/// Build metadata printed by `pcl version --json`
+pub fn version_json() -> serde_json::Value {
+    serde_json::json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "commit": env!("VERGEN_GIT_SHA"),
+        "build_timestamp": env!("VERGEN_BUILD_TIMESTAMP"),
+        "default_platform_url": DEFAULT_PLATFORM_URL,
+    })
+}
+
 #[derive(Parser)]
 #[command(
     name = "pcl",
@@ -54,13 +69,29 @@ pub enum Commands {
     Auth(AuthCommand),
     #[command(about = "Manage configuration")]
     Config(ConfigArgs),
+    #[command(name = "cache")]
+    Cache(CacheArgs),
     #[command(name = "build")]
     Build(BuildArgs),
+    #[command(name = "lint")]
+    Lint(LintArgs),
     #[cfg(feature = "credible")]
     #[command(name = "verify")]
     Verify(VerifyArgs),
     #[command(name = "download")]
     Download(DownloadArgs),
+    #[command(name = "doctor")]
+    Doctor(DoctorArgs),
+    #[command(
+        name = "version",
+        about = "Print version and build information",
+        after_help = "Example: pcl version --json"
+    )]
+    Version {
+        /// Print version and build metadata as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[cfg(test)]
@@ -68,12 +99,28 @@ mod tests {
     use super::*;
     use clap::Parser;
 
+    #[test]
+    fn parses_version_command() {
+        let cli = Cli::try_parse_from(["pcl", "version", "--json"]).unwrap();
+        assert!(matches!(cli.command, Commands::Version { json: true }));
+
+        let version = version_json();
+        assert_eq!(version["version"], env!("CARGO_PKG_VERSION"));
+        assert!(version["commit"].is_string());
+    }
+
     #[test]
     fn parses_config_show_command() {
         let cli = Cli::try_parse_from(["pcl", "config", "show"]).unwrap();
         assert!(matches!(cli.command, Commands::Config(_)));
     }
 
+    #[test]
+    fn parses_cache_command() {
+        let cli = Cli::try_parse_from(["pcl", "cache", "clear"]).unwrap();
+        assert!(matches!(cli.command, Commands::Cache(_)));
+    }
+
     #[test]
     fn parses_apply_command() {
         let cli =
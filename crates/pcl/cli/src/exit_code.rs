@@ -0,0 +1,187 @@
+//! Stable process exit codes, so scripts can tell failure categories apart.
+//!
+//! The codes are part of the CLI contract documented in the README; only add
+//! new ones, never renumber.
+
+#[cfg(feature = "credible")]
+use pcl_core::error::VerifyError;
+use pcl_core::{
+    download::DownloadError,
+    error::{
+        ApplyError,
+        AuthError,
+        ConfigError,
+    },
+};
+use pcl_phoundry::error::PhoundryError;
+use std::error::Error;
+
+/// Any failure not covered by a more specific code
+pub const GENERAL: i32 = 1;
+/// Not logged in, expired token or rejected login
+pub const AUTH: i32 = 2;
+/// The platform could not be reached or returned an error
+pub const NETWORK: i32 = 3;
+/// Contracts failed to compile or could not be found in the build output
+pub const COMPILATION: i32 = 4;
+/// Invalid arguments, flags or configuration files
+pub const BAD_ARGS: i32 = 5;
+/// Cancelled with Ctrl-C, following the shell convention of 128 + SIGINT
+pub const INTERRUPTED: i32 = 130;
+
+/// Maps a top-level error to its exit code.
+///
+/// The first error in the chain with a known category decides the code.
+pub fn exit_code(err: &color_eyre::Report) -> i32 {
+    err.chain().find_map(category).unwrap_or(GENERAL)
+}
+
+fn category(err: &(dyn Error + 'static)) -> Option<i32> {
+    if let Some(err) = err.downcast_ref::<ApplyError>() {
+        return Some(apply_code(err));
+    }
+    if let Some(err) = err.downcast_ref::<AuthError>() {
+        return Some(auth_code(err));
+    }
+    if let Some(err) = err.downcast_ref::<ConfigError>() {
+        return config_code(err);
+    }
+    if let Some(err) = err.downcast_ref::<DownloadError>() {
+        return Some(download_code(err));
+    }
+    #[cfg(feature = "credible")]
+    if let Some(err) = err.downcast_ref::<VerifyError>() {
+        return Some(verify_code(err));
+    }
+    if let Some(err) = err.downcast_ref::<Box<PhoundryError>>() {
+        return Some(phoundry_code(err));
+    }
+    if let Some(err) = err.downcast_ref::<PhoundryError>() {
+        return Some(phoundry_code(err));
+    }
+    None
+}
+
+fn apply_code(err: &ApplyError) -> i32 {
+    match err {
+        ApplyError::NoAuthToken
+        | ApplyError::TokenExpired
+        | ApplyError::TokenIssuerMismatch { .. } => AUTH,
+        ApplyError::Api { .. } | ApplyError::RateLimited { .. } => NETWORK,
+        ApplyError::BuildFailed(_) => COMPILATION,
+        ApplyError::Toml(_)
+        | ApplyError::InvalidConfig(_)
+        | ApplyError::ConstructorArgs { .. }
+        | ApplyError::SourceTooLarge { .. }
+        | ApplyError::JsonConfirmationRequiresYes
+        | ApplyError::PromptRequired { .. } => BAD_ARGS,
+        _ => GENERAL,
+    }
+}
+
+fn auth_code(err: &AuthError) -> i32 {
+    match err {
+        AuthError::AuthRequestFailed(_)
+        | AuthError::StatusRequestFailed(_)
+        | AuthError::ServerError(_) => NETWORK,
+        AuthError::ConfigError(err) => config_code(err).unwrap_or(GENERAL),
+        // A failed prompt says nothing about the credentials
+        AuthError::PromptFailed(_) => GENERAL,
+        _ => AUTH,
+    }
+}
+
+/// Config errors without a category fall through to the rest of the chain.
+fn config_code(err: &ConfigError) -> Option<i32> {
+    match err {
+        ConfigError::NotAuthenticated => Some(AUTH),
+        ConfigError::ParseError(_)
+        | ConfigError::UnsupportedVersion(_)
+        | ConfigError::InvalidVersion(_)
+        | ConfigError::ValidationFailed(_)
+        | ConfigError::ExportExists(_)
+        | ConfigError::ConfirmationRequired => Some(BAD_ARGS),
+        _ => None,
+    }
+}
+
+fn download_code(err: &DownloadError) -> i32 {
+    match err {
+        DownloadError::NoAuthToken | DownloadError::TokenExpired => AUTH,
+        DownloadError::Api { .. } | DownloadError::RateLimited { .. } => NETWORK,
+        DownloadError::MissingIdentifier | DownloadError::InvalidConfig(_) => BAD_ARGS,
+        _ => GENERAL,
+    }
+}
+
+#[cfg(feature = "credible")]
+fn verify_code(err: &VerifyError) -> i32 {
+    match err {
+        VerifyError::BuildFailed(_) => COMPILATION,
+        VerifyError::Config(_) | VerifyError::ConstructorArgs(_) | VerifyError::AbiEncode(_) => {
+            BAD_ARGS
+        }
+        _ => GENERAL,
+    }
+}
+
+fn phoundry_code(err: &PhoundryError) -> i32 {
+    match err {
+        PhoundryError::CompilationError(_)
+        | PhoundryError::SolcError(_)
+        | PhoundryError::ContractNotFound(..)
+        | PhoundryError::WarningsFound(_)
+        | PhoundryError::NoSourceFilesFound => COMPILATION,
+        PhoundryError::InvalidFoundryProfile(..)
+        | PhoundryError::AmbiguousContract(..)
+        | PhoundryError::InvalidPath(_)
+        | PhoundryError::DirectoryNotFound(_)
+        | PhoundryError::NoTestFilesFound(_)
+        | PhoundryError::LockfileChanged(_) => BAD_ARGS,
+        _ => GENERAL,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use color_eyre::Report;
+
+    #[test]
+    fn maps_errors_to_exit_codes() {
+        assert_eq!(exit_code(&Report::new(ApplyError::NoAuthToken)), AUTH);
+        assert_eq!(
+            exit_code(&Report::new(ApplyError::Api {
+                endpoint: "/projects".to_string(),
+                status: Some(502),
+                body: String::new(),
+            })),
+            NETWORK
+        );
+        assert_eq!(
+            exit_code(&Report::new(ApplyError::BuildFailed(Box::new(
+                PhoundryError::NoSourceFilesFound
+            )))),
+            COMPILATION
+        );
+        assert_eq!(
+            exit_code(&Report::new(Box::new(PhoundryError::DirectoryNotFound(
+                "missing".into()
+            )))),
+            BAD_ARGS
+        );
+        assert_eq!(
+            exit_code(&Report::new(ApplyError::RateLimited {
+                endpoint: "/projects".to_string(),
+                retries: 3,
+            })),
+            NETWORK
+        );
+        assert_eq!(exit_code(&Report::new(AuthError::Timeout(3))), AUTH);
+        assert_eq!(
+            exit_code(&Report::new(ConfigError::DeleteCancelled)),
+            GENERAL
+        );
+        assert_eq!(exit_code(&color_eyre::eyre::eyre!("other")), GENERAL);
+    }
+}
@@ -1,4 +1,5 @@
 mod cli;
+mod exit_code;
 
 use crate::cli::{
     Cli,
@@ -7,59 +8,187 @@ use crate::cli::{
 use clap::Parser;
 use color_eyre::{
     Result,
-    eyre::Report,
+    eyre::{
+        Report,
+        eyre,
+    },
+};
+use pcl_common::args::CliArgs;
+use pcl_core::{
+    config::CliConfig,
+    error::ConfigError,
+    run_blocking,
 };
-use pcl_core::config::CliConfig;
 use serde_json::json;
+use tracing_subscriber::EnvFilter;
+
+/// Installs a stderr logger when `--log-level` or `RUST_LOG` is set.
+///
+/// Logs never go to stdout so `--json` output stays machine-readable.
+fn init_tracing(args: &CliArgs) -> Result<()> {
+    let filter = match args.log_level.as_deref() {
+        Some(level) => EnvFilter::try_new(level)?,
+        None => {
+            match EnvFilter::try_from_default_env() {
+                Ok(filter) => filter,
+                Err(_) => return Ok(()),
+            }
+        }
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(!args.no_color())
+        .try_init()
+        .map_err(|e| eyre!(e))
+}
+
+/// Registers the Ctrl-C handler immediately and returns a future that
+/// resolves on the next interrupt.
+///
+/// `tokio::signal::ctrl_c` only registers on first poll, which would leave a
+/// window where the default handler still kills the process.
+fn interrupt_listener() -> Result<impl Future<Output = ()>> {
+    #[cfg(unix)]
+    let mut signal = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::interrupt())?;
+    #[cfg(windows)]
+    let mut signal = tokio::signal::windows::ctrl_c()?;
+    Ok(async move {
+        signal.recv().await;
+    })
+}
+
+/// Saves the config changes made by the command.
+///
+/// Runs on the blocking pool because waiting for the config lock can sleep.
+async fn write_config(
+    config: CliConfig,
+    loaded_config: CliConfig,
+    args: &CliArgs,
+) -> Result<(), ConfigError> {
+    let args = args.clone();
+    run_blocking(move || config.write_changes(&loaded_config, &args)).await
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(err) if err.use_stderr() => {
+            let _ = err.print();
+            std::process::exit(exit_code::BAD_ARGS);
+        }
+        // --help and --version
+        Err(err) => err.exit(),
+    };
+
+    let mut hook = color_eyre::config::HookBuilder::default();
+    if cli.args.no_color() {
+        colored::control::set_override(false);
+        hook = hook.theme(color_eyre::config::Theme::new());
+    }
+
     // Configure color_eyre to hide location information and backtrace messages
-    color_eyre::config::HookBuilder::default()
-        .display_location_section(true)
+    hook.display_location_section(true)
         .display_env_section(false)
         .install()?;
+    init_tracing(&cli.args)?;
 
-    let cli = Cli::parse();
     let mut config = CliConfig::read_from_file(&cli.args).unwrap_or_default();
+    let loaded_config = config.clone();
 
     // TODO(Odysseas): Convert these commands to return strings to print for json output
     // We can also use something similar like the shell macro from Foundry
     // where a global static lazy is used to signal to every print statement
     // whether it should be a noop or print to stdout/stderr.
 
-    let result = async {
+    let command = async {
         match cli.command {
             #[cfg(feature = "credible")]
             Commands::Test(phorge) => {
-                phorge.run().await?;
+                phorge.run(cli.args.json_output()).await?;
             }
             Commands::Apply(apply) => {
-                apply.run(&cli.args, &config).await?;
+                apply.run(&cli.args, &mut config).await?;
             }
             Commands::Auth(auth_cmd) => {
-                auth_cmd.run(&mut config).await?;
+                auth_cmd.run(&cli.args, &mut config).await?;
             }
             Commands::Config(config_cmd) => {
-                config_cmd.run(&mut config)?;
+                let mut updated = config.clone();
+                let args = cli.args.clone();
+                let (result, updated) = run_blocking(move || {
+                    let result = config_cmd.run(&args, &mut updated);
+                    (result, updated)
+                })
+                .await;
+                config = updated;
+                result?;
+            }
+            Commands::Cache(cache_cmd) => {
+                let args = cli.args.clone();
+                run_blocking(move || cache_cmd.run(&args)).await?;
             }
             Commands::Build(build_cmd) => {
-                build_cmd.run()?;
+                let args = cli.args.clone();
+                run_blocking(move || build_cmd.run(&args)).await?;
+            }
+            Commands::Lint(lint_cmd) => {
+                let args = cli.args.clone();
+                run_blocking(move || lint_cmd.run(&args)).await?;
             }
             #[cfg(feature = "credible")]
             Commands::Verify(verify_cmd) => {
-                verify_cmd.run(&cli.args)?;
+                let config = config.clone();
+                let args = cli.args.clone();
+                run_blocking(move || verify_cmd.run(&args, &config)).await?;
             }
             Commands::Download(download_cmd) => {
                 download_cmd.run(&cli.args, &config).await?;
             }
+            Commands::Doctor(doctor_cmd) => {
+                doctor_cmd.run(&cli.args, &config).await?;
+            }
+            Commands::Version { json } => {
+                if json || cli.args.json_output() {
+                    println!("{}", serde_json::to_string_pretty(&cli::version_json())?);
+                } else {
+                    println!("pcl {}", cli::version_message());
+                }
+            }
         }
-        config.write_to_file(&cli.args)?;
         Ok::<_, Report>(())
-    }
-    .await;
+    };
+
+    // Listening for Ctrl-C replaces the default handler, so the command is
+    // dropped cleanly instead of the process dying mid-write. The listener is
+    // registered before the command starts, and blocking work runs off this
+    // task, so an interrupt is noticed at any point.
+    let interrupted = interrupt_listener()?;
+    let outcome = tokio::select! {
+        biased;
+        () = interrupted => None,
+        result = command => Some(result),
+    };
+    let Some(result) = outcome else {
+        pcl_common::spinner::clear_active();
+        // Keep anything the command finished before the interrupt (errors are non-fatal)
+        let _ = write_config(config, loaded_config, &cli.args).await;
+        // Move past a prompt left on the current line
+        eprintln!("\nInterrupted");
+        std::process::exit(exit_code::INTERRUPTED);
+    };
+    let result = match result {
+        Ok(()) => {
+            write_config(config, loaded_config, &cli.args)
+                .await
+                .map_err(Report::from)
+        }
+        Err(err) => Err(err),
+    };
 
     if let Err(err) = result {
+        let code = exit_code::exit_code(&err);
         if cli.args.json_output() {
             eprintln!(
                 "{}",
@@ -67,13 +196,14 @@ async fn main() -> Result<()> {
                     "status": "error",
                     "error": {
                         "message": err.to_string(),
+                        "exit_code": code,
                     }
                 })
             );
-            std::process::exit(1);
         } else {
-            return Err(err);
+            eprintln!("Error: {err:?}");
         }
+        std::process::exit(code);
     }
 
     Ok(())
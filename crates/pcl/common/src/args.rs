@@ -5,6 +5,18 @@ use std::path::PathBuf;
 pub struct CliArgs {
     #[clap(short, long)]
     pub json: bool,
+    /// Print compiler output, including warnings and full solc diagnostics
+    #[clap(short, long)]
+    pub verbose: bool,
+    /// Suppress spinners, banners and progress messages; results and errors are still printed
+    #[clap(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// Disable colored output. Also enabled by a non-empty `NO_COLOR` variable
+    #[clap(long)]
+    pub no_color: bool,
+    /// Log filter written to stderr, e.g. `debug` or `pcl_core=trace`. Falls back to `RUST_LOG`
+    #[clap(long, value_name = "LEVEL")]
+    pub log_level: Option<String>,
     #[clap(hide = true)]
     pub config_dir: Option<PathBuf>,
 }
@@ -13,6 +25,19 @@ impl CliArgs {
     pub fn json_output(&self) -> bool {
         self.json
     }
+
+    pub fn verbose(&self) -> bool {
+        self.verbose
+    }
+
+    pub fn quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// Whether colored output should be disabled, following <https://no-color.org>
+    pub fn no_color(&self) -> bool {
+        self.no_color || std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+    }
 }
 
 #[cfg(test)]
@@ -29,6 +54,32 @@ mod tests {
         assert!(args.json_output());
     }
 
+    #[test]
+    fn parses_verbose_flag() {
+        let args = CliArgs::try_parse_from(["cli", "-v"]).expect("should parse");
+        assert!(args.verbose());
+        assert!(!CliArgs::default().verbose());
+    }
+
+    #[test]
+    fn parses_quiet_flag() {
+        let args = CliArgs::try_parse_from(["cli", "-q"]).expect("should parse");
+        assert!(args.quiet());
+        assert!(CliArgs::try_parse_from(["cli", "--quiet", "--verbose"]).is_err());
+    }
+
+    #[test]
+    fn parses_no_color_flag() {
+        let args = CliArgs::try_parse_from(["cli", "--no-color"]).expect("should parse");
+        assert!(args.no_color());
+    }
+
+    #[test]
+    fn parses_log_level() {
+        let args = CliArgs::try_parse_from(["cli", "--log-level", "debug"]).expect("should parse");
+        assert_eq!(args.log_level.as_deref(), Some("debug"));
+    }
+
     #[test]
     fn config_dir_can_be_overridden() {
         let args = CliArgs {
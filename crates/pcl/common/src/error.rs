@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors that can occur while reading build artifacts
+#[derive(Error, Debug)]
+pub enum ArtifactError {
+    /// No artifact file exists for the contract in any of the searched locations
+    #[error(
+        "Failed to find artifact for {contract_name}, searched: {}",
+        searched.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", ")
+    )]
+    NotFound {
+        contract_name: String,
+        searched: Vec<PathBuf>,
+    },
+
+    /// The artifact file exists but could not be opened
+    #[error("Failed to open artifact {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The artifact file is not valid JSON
+    #[error("Failed to parse artifact {}: {source}", path.display())]
+    InvalidJson {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    /// A required field is missing from the artifact
+    #[error("Artifact is missing field `{0}`")]
+    MissingField(&'static str),
+
+    /// The contract is not listed in the artifact's compilation target
+    #[error("Failed to find {0} in the artifact compilation target")]
+    ContractNotInCompilationTarget(String),
+}
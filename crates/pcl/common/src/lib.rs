@@ -1,30 +1,50 @@
 #![allow(clippy::must_use_candidate)]
 
 pub mod args;
+pub mod error;
+pub mod spinner;
 pub mod utils;
 
+/// Assertion file extensions tried when none are configured, most preferred first
+pub const DEFAULT_ASSERTION_EXTENSIONS: &[&str] = &[".a.sol", ".sol"];
+
 #[derive(Clone)]
 pub struct Assertion {
     file_name: Option<String>,
     contract_name: String,
+    extensions: Vec<String>,
 }
 
 impl Assertion {
-    const SUPPORTED_EXTENSIONS: &'static [&'static str] = &[".a.sol", ".sol"];
-
     pub fn new(file_name: Option<String>, contract_name: String) -> Self {
         Self {
             file_name,
             contract_name,
+            extensions: default_extensions(),
+        }
+    }
+
+    /// Replaces the file extensions tried by [`Assertion::get_paths`].
+    ///
+    /// An empty list keeps [`DEFAULT_ASSERTION_EXTENSIONS`].
+    #[must_use]
+    pub fn with_extensions(mut self, extensions: &[String]) -> Self {
+        if !extensions.is_empty() {
+            self.extensions = extensions.to_vec();
         }
+        self
     }
 
+    /// Candidate source file names, most preferred first.
+    ///
+    /// Without an explicit file name, one `<name><extension>` per configured
+    /// extension; by default `<name>.a.sol` is preferred over `<name>.sol`.
     pub fn get_paths(&self) -> Vec<String> {
         if let Some(file_name) = &self.file_name {
             vec![file_name.clone()]
         } else {
             let mut file_names = Vec::new();
-            for ext in Self::SUPPORTED_EXTENSIONS {
+            for ext in &self.extensions {
                 let path = format!("{}{}", self.contract_name, ext);
                 file_names.push(path);
             }
@@ -36,3 +56,55 @@ impl Assertion {
         &self.contract_name
     }
 }
+
+/// Strips the first matching assertion extension from a file name.
+///
+/// An empty `extensions` list uses [`DEFAULT_ASSERTION_EXTENSIONS`].
+pub fn strip_assertion_extension<'a>(file_name: &'a str, extensions: &[String]) -> Option<&'a str> {
+    if extensions.is_empty() {
+        DEFAULT_ASSERTION_EXTENSIONS
+            .iter()
+            .find_map(|ext| file_name.strip_suffix(ext))
+    } else {
+        extensions
+            .iter()
+            .find_map(|ext| file_name.strip_suffix(ext.as_str()))
+    }
+}
+
+fn default_extensions() -> Vec<String> {
+    DEFAULT_ASSERTION_EXTENSIONS
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_paths_uses_custom_extensions() {
+        let assertion = Assertion::new(None, "Guard".to_string());
+        assert_eq!(assertion.get_paths(), vec!["Guard.a.sol", "Guard.sol"]);
+
+        let assertion = assertion.with_extensions(&[".assert.sol".to_string()]);
+        assert_eq!(assertion.get_paths(), vec!["Guard.assert.sol"]);
+        assert_eq!(
+            assertion.with_extensions(&[]).get_paths(),
+            vec!["Guard.assert.sol"]
+        );
+    }
+
+    #[test]
+    fn strips_assertion_extensions() {
+        assert_eq!(strip_assertion_extension("Guard.a.sol", &[]), Some("Guard"));
+        assert_eq!(strip_assertion_extension("Guard.sol", &[]), Some("Guard"));
+        let custom = [".assert.sol".to_string()];
+        assert_eq!(
+            strip_assertion_extension("Guard.assert.sol", &custom),
+            Some("Guard")
+        );
+        assert_eq!(strip_assertion_extension("Guard.sol", &custom), None);
+    }
+}
@@ -0,0 +1,43 @@
+//! Registry of running spinners, so an interrupt can clear them before exiting.
+//!
+//! Dropping a spinner mid-tick leaves a half-drawn line on the terminal; the
+//! Ctrl-C handler calls [`clear_active`] to erase it instead.
+
+use indicatif::ProgressBar;
+use std::sync::{
+    Mutex,
+    PoisonError,
+};
+
+static ACTIVE: Mutex<Vec<ProgressBar>> = Mutex::new(Vec::new());
+
+/// Records `spinner` so [`clear_active`] can clear it if it is still running.
+pub fn track(spinner: &ProgressBar) {
+    let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+    active.retain(|spinner| !spinner.is_finished());
+    active.push(spinner.clone());
+}
+
+/// Clears every tracked spinner that has not finished yet.
+pub fn clear_active() {
+    let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+    for spinner in active.drain(..) {
+        if !spinner.is_finished() {
+            spinner.finish_and_clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clear_active_finishes_running_spinners() {
+        let spinner = ProgressBar::hidden();
+        track(&spinner);
+        assert!(!spinner.is_finished());
+        clear_active();
+        assert!(spinner.is_finished());
+    }
+}
@@ -1,8 +1,12 @@
-// We allow panics as this should be invoked at startup time
-#![allow(clippy::missing_panics_doc)]
-use std::path::Path;
+use std::path::{
+    Path,
+    PathBuf,
+};
 
-use crate::Assertion;
+use crate::{
+    Assertion,
+    error::ArtifactError,
+};
 
 #[derive(Debug)]
 pub struct BuildInfo {
@@ -11,23 +15,54 @@ pub struct BuildInfo {
     pub bytecode: String,
 }
 
+/// Lists the artifact files that exist for `input` in `out_dir`, most preferred first
+///
+/// More than one entry means several candidate source files define the contract,
+/// e.g. both `Name.a.sol` and `Name.sol`.
+pub fn existing_artifacts(input: &Assertion, out_dir: &Path) -> Vec<PathBuf> {
+    input
+        .get_paths()
+        .iter()
+        .map(|file_name| artifact_path(input, out_dir, file_name))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+fn artifact_path(input: &Assertion, out_dir: &Path, file_name: &str) -> PathBuf {
+    out_dir.join(format!("{}/{}.json", file_name, input.contract_name()))
+}
+
 /// Reads a contract artifact
 /// Input can be specified in two patterns
 /// 1. ${`file_name[.sol, .a.sol]`}:${`contract_name`}
 /// 2. ${`contract_name`} (`file_name` is assumed to be the same as `contract_name`, with .sol extension)
 ///
 /// `out_dir` is the output directory of the build artifact
-pub fn read_artifact(input: &Assertion, out_dir: &Path) -> serde_json::Value {
-    let file_names = input.get_paths();
-    // Try each file name until we find one that exists
-    for file_name in &file_names {
-        let path = out_dir.join(format!("{}/{}.json", file_name, input.contract_name()));
-        if path.exists() {
-            let file = std::fs::File::open(&path).expect("Failed to open file");
-            return serde_json::from_reader(file).expect("Failed to parse JSON");
+///
+/// When both `.a.sol` and `.sol` artifacts exist, the `.a.sol` one is read; callers
+/// that want to warn about the ambiguity can check [`existing_artifacts`].
+pub fn read_artifact(
+    input: &Assertion,
+    out_dir: &Path,
+) -> Result<serde_json::Value, ArtifactError> {
+    let existing = existing_artifacts(input, out_dir);
+    let Some(path) = existing.first().cloned() else {
+        return Err(ArtifactError::NotFound {
+            contract_name: input.contract_name().to_string(),
+            searched: input
+                .get_paths()
+                .iter()
+                .map(|file_name| artifact_path(input, out_dir, file_name))
+                .collect(),
+        });
+    };
+    let file = std::fs::File::open(&path).map_err(|source| {
+        ArtifactError::Io {
+            path: path.clone(),
+            source,
         }
-    }
-    panic!("Failed to find artifact for {}", input.contract_name());
+    })?;
+    serde_json::from_reader(file).map_err(|source| ArtifactError::InvalidJson { path, source })
 }
 
 /// Reads deployment bytecode from a contract artifact
@@ -36,19 +71,24 @@ pub fn read_artifact(input: &Assertion, out_dir: &Path) -> serde_json::Value {
 /// 2. ${`contract_name`} (`file_name` is assumed to be the same as `contract_name`, with .sol extension)
 ///
 /// `out_dir` is the output directory of the build artifact
-pub fn bytecode(artifact: &serde_json::Value) -> String {
+pub fn bytecode(artifact: &serde_json::Value) -> Result<String, ArtifactError> {
     let bytecode = artifact["bytecode"]["object"]
         .as_str()
-        .expect("Failed to read bytecode");
-    bytecode.to_string()
+        .ok_or(ArtifactError::MissingField("bytecode.object"))?;
+    Ok(bytecode.to_string())
 }
 
-pub fn compilation_target(input: &Assertion, artifact: &serde_json::Value) -> String {
+pub fn compilation_target(
+    input: &Assertion,
+    artifact: &serde_json::Value,
+) -> Result<String, ArtifactError> {
     // The compilationTarget is a map with a single key-value pair where the key is the file path
     // and the value is the contract name. We need to extract the file path (key).
     let compilation_target = artifact["metadata"]["settings"]["compilationTarget"]
         .as_object()
-        .expect("Failed to read compilation target as object");
+        .ok_or(ArtifactError::MissingField(
+            "metadata.settings.compilationTarget",
+        ))?;
     // Get the compilation target of the contract with name contract_name
     compilation_target
         .iter()
@@ -59,21 +99,109 @@ pub fn compilation_target(input: &Assertion, artifact: &serde_json::Value) -> St
                 None
             }
         })
-        .expect("Failed to find contract in compilation target")
+        .ok_or_else(|| {
+            ArtifactError::ContractNotInCompilationTarget(input.contract_name().to_string())
+        })
 }
 
-pub fn compiler_version(artifact: &serde_json::Value) -> String {
+pub fn compiler_version(artifact: &serde_json::Value) -> Result<String, ArtifactError> {
     let compiler_version = artifact["metadata"]["compiler"]["version"]
         .as_str()
-        .expect("failed to read compiler version");
-    compiler_version.to_string()
+        .ok_or(ArtifactError::MissingField("metadata.compiler.version"))?;
+    Ok(compiler_version.to_string())
+}
+
+pub fn get_build_info(input: &Assertion, out_dir: &Path) -> Result<BuildInfo, ArtifactError> {
+    let artifact = read_artifact(input, out_dir)?;
+    Ok(BuildInfo {
+        compiler_version: compiler_version(&artifact)?,
+        compilation_target: compilation_target(input, &artifact)?,
+        bytecode: bytecode(&artifact)?,
+    })
 }
 
-pub fn get_build_info(input: &Assertion, out_dir: &Path) -> BuildInfo {
-    let artifact = read_artifact(input, out_dir);
-    BuildInfo {
-        compiler_version: compiler_version(&artifact),
-        compilation_target: compilation_target(input, &artifact),
-        bytecode: bytecode(&artifact),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn read_artifact_reports_searched_paths() {
+        let out_dir = tempfile::TempDir::new().unwrap();
+        let input = Assertion::new(None, "Missing".to_string());
+
+        let err = read_artifact(&input, out_dir.path()).unwrap_err();
+        match err {
+            ArtifactError::NotFound {
+                contract_name,
+                searched,
+            } => {
+                assert_eq!(contract_name, "Missing");
+                assert_eq!(
+                    searched,
+                    vec![
+                        out_dir.path().join("Missing.a.sol/Missing.json"),
+                        out_dir.path().join("Missing.sol/Missing.json"),
+                    ]
+                );
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn existing_artifacts_lists_assertion_variant_first() {
+        let out_dir = tempfile::TempDir::new().unwrap();
+        let input = Assertion::new(None, "Counter".to_string());
+        assert!(existing_artifacts(&input, out_dir.path()).is_empty());
+
+        for file_name in ["Counter.sol", "Counter.a.sol"] {
+            let artifact_dir = out_dir.path().join(file_name);
+            std::fs::create_dir_all(&artifact_dir).unwrap();
+            std::fs::write(artifact_dir.join("Counter.json"), "{}").unwrap();
+        }
+
+        assert_eq!(
+            existing_artifacts(&input, out_dir.path()),
+            vec![
+                out_dir.path().join("Counter.a.sol/Counter.json"),
+                out_dir.path().join("Counter.sol/Counter.json"),
+            ]
+        );
+        assert!(read_artifact(&input, out_dir.path()).is_ok());
+    }
+
+    #[test]
+    fn get_build_info_reads_fields() {
+        let out_dir = tempfile::TempDir::new().unwrap();
+        let artifact_dir = out_dir.path().join("Counter.sol");
+        std::fs::create_dir_all(&artifact_dir).unwrap();
+        std::fs::write(
+            artifact_dir.join("Counter.json"),
+            json!({
+                "bytecode": { "object": "0x6000" },
+                "metadata": {
+                    "compiler": { "version": "0.8.28" },
+                    "settings": { "compilationTarget": { "src/Counter.sol": "Counter" } }
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let info =
+            get_build_info(&Assertion::new(None, "Counter".to_string()), out_dir.path()).unwrap();
+        assert_eq!(info.bytecode, "0x6000");
+        assert_eq!(info.compiler_version, "0.8.28");
+        assert_eq!(info.compilation_target, "src/Counter.sol");
+    }
+
+    #[test]
+    fn bytecode_reports_missing_field() {
+        let err = bytecode(&json!({})).unwrap_err();
+        assert!(matches!(
+            err,
+            ArtifactError::MissingField("bytecode.object")
+        ));
     }
 }
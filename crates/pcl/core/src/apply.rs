@@ -7,9 +7,22 @@ use crate::verify::{
     run_verification,
 };
 use crate::{
+    DEFAULT_API_TIMEOUT_SECS,
+    DEFAULT_API_VERSION,
     DEFAULT_PLATFORM_URL,
-    client::authenticated_client,
-    config::CliConfig,
+    client::{
+        RateLimitError,
+        api_base_url,
+        authenticated_client,
+        parse_api_version,
+        retry_rate_limited,
+    },
+    config::{
+        CliConfig,
+        ProjectConfig,
+        UserAuth,
+    },
+    constructor_args::parse_constructor_args,
     credible_config::{
         CredibleToml,
         assertion_contract_name,
@@ -19,23 +32,30 @@ use crate::{
 };
 use alloy_primitives::Bytes;
 use clap::ValueHint;
+use colored::Colorize;
 use dapp_api_client::generated::client::{
     Client as GeneratedClient,
+    Error as ApiError,
     types::{
         GetProjectsResponseItem,
         PostProjectsProjectIdReleasesBody,
         PostProjectsProjectIdReleasesBodyContractsValue,
         PostProjectsProjectIdReleasesBodyContractsValueAssertionsItem,
+        PostProjectsProjectIdReleasesBodyContractsValueAssertionsItemCompilerVersion,
         PostProjectsProjectIdReleasesResponse,
     },
 };
 use inquire::Select;
 use pcl_common::args::CliArgs;
-use pcl_phoundry::build_and_flatten::BuildAndFlattenArgs;
+use pcl_phoundry::{
+    build_and_flatten::BuildAndFlattenArgs,
+    compile::ProfileArgs,
+};
 use serde::Serialize;
 use std::{
     collections::HashMap,
     io::{
+        IsTerminal,
         Write,
         stderr,
         stdin,
@@ -44,10 +64,17 @@ use std::{
         Path,
         PathBuf,
     },
+    time::Duration,
 };
 use url::Url;
 use uuid::Uuid;
 
+/// Maximum number of characters of a response body included in API errors
+const MAX_ERROR_BODY_CHARS: usize = 512;
+
+/// Flattened sources above this size get a warning before submission
+const SOURCE_SIZE_WARNING_BYTES: usize = 1024 * 1024;
+
 #[derive(clap::Parser, Debug)]
 #[command(
     name = "apply",
@@ -74,6 +101,18 @@ pub struct ApplyArgs {
     #[arg(long, help = "Emit machine-readable output for this command")]
     pub json: bool,
 
+    #[arg(
+        long = "project-id",
+        help = "Project to apply to. Skips project selection; must match `project_id` in credible.toml if both are set"
+    )]
+    pub project_id: Option<Uuid>,
+
+    #[arg(
+        long,
+        help = "Do not preselect or remember the project used by the last successful apply"
+    )]
+    pub no_remember_project: bool,
+
     #[arg(
         long = "yes",
         visible_alias = "auto-approve",
@@ -81,6 +120,55 @@ pub struct ApplyArgs {
     )]
     pub yes: bool,
 
+    #[arg(
+        long,
+        conflicts_with = "yes",
+        help = "Build, verify and preview the release without creating it; with --json, include the planned payload"
+    )]
+    pub dry_run: bool,
+
+    #[arg(
+        long,
+        help = "Fail instead of prompting for input; implied when stdin is not a terminal"
+    )]
+    pub non_interactive: bool,
+
+    #[arg(
+        long,
+        help = "Fail instead of warning when the stored login was issued by a different platform URL"
+    )]
+    pub strict: bool,
+
+    #[arg(
+        long,
+        help = "Always reflatten assertions instead of reusing cached flattened sources"
+    )]
+    pub no_cache: bool,
+
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Fail before submitting if a flattened assertion source exceeds this size"
+    )]
+    pub max_source_bytes: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Prepend a comment listing the original source files and remappings to each flattened source"
+    )]
+    pub annotate_sources: bool,
+
+    #[arg(
+        long,
+        value_name = "VERSION",
+        value_parser = parse_compiler_version,
+        help = "Compiler version sent with every assertion instead of the one from the build metadata, e.g. v0.8.28+commit.7893614a"
+    )]
+    pub compiler_version: Option<String>,
+
+    #[command(flatten)]
+    pub profile_args: ProfileArgs,
+
     #[arg(
         short = 'u',
         long = "api-url",
@@ -90,6 +178,24 @@ pub struct ApplyArgs {
         help = "Base URL for the platform API"
     )]
     pub api_url: url::Url,
+
+    #[arg(
+        long = "api-version",
+        env = "PCL_API_VERSION",
+        default_value = DEFAULT_API_VERSION,
+        value_parser = parse_api_version,
+        help = "Platform API version to use (e.g. v1)"
+    )]
+    pub api_version: String,
+
+    #[arg(
+        long = "api-timeout-secs",
+        env = "PCL_API_TIMEOUT_SECS",
+        default_value_t = DEFAULT_API_TIMEOUT_SECS,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Seconds to wait for each platform API request"
+    )]
+    pub api_timeout_secs: u64,
 }
 
 #[derive(Debug, Serialize)]
@@ -101,29 +207,101 @@ struct ApplyJsonOutput {
     preview: Option<PreviewResponse>,
     applied: bool,
     release: Option<PostProjectsProjectIdReleasesResponse>,
+    /// Release body that would be submitted, only set for `--dry-run`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payload: Option<PostProjectsProjectIdReleasesBody>,
+}
+
+/// Build settings for [`build_release`], mirroring the `pcl apply` flags.
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseOptions {
+    pub verbose: bool,
+    pub no_cache: bool,
+    /// Reject flattened sources larger than this many bytes
+    pub max_source_bytes: Option<usize>,
+    /// Prepend the list of original source files to each flattened source
+    pub annotate_sources: bool,
+    /// Assertion file suffixes; empty means the defaults
+    pub assertion_extensions: Vec<String>,
+    /// Compiler version to record on every assertion instead of the build metadata
+    pub compiler_version: Option<String>,
+    /// Foundry profile to build with instead of the default one
+    pub profile: Option<String>,
+    /// Build offline and fail if pinned dependency files would change
+    pub locked: bool,
+}
+
+/// A release payload ready to preview or submit.
+#[derive(Debug, Clone)]
+pub struct BuiltRelease {
+    pub payload: PostProjectsProjectIdReleasesBody,
+    /// Display name and deployment bytecode of every assertion, for local verification
+    pub deployment_bytecodes: Vec<(String, Bytes)>,
+    /// Non-fatal problems found while building, e.g. unusually large sources
+    pub warnings: Vec<String>,
 }
 
 impl ApplyArgs {
-    pub async fn run(&self, cli_args: &CliArgs, config: &CliConfig) -> Result<(), ApplyError> {
+    pub async fn run(&self, cli_args: &CliArgs, config: &mut CliConfig) -> Result<(), ApplyError> {
         let json_output = cli_args.json_output() || self.json;
+        // Fail before compiling when the stored token can only be rejected
+        if config.auth.as_ref().is_some_and(UserAuth::is_expired) {
+            return Err(ApplyError::TokenExpired);
+        }
         let root = canonicalize_root(&self.root)?;
+        discover_project_config(config, &root)?;
+        self.check_token_issuer(config)?;
         let config_path = root.join(&self.config);
         let credible = CredibleToml::from_path(&config_path)?;
-        let project_id = match credible.project_id {
+        let project_id = match resolve_project_id(self.project_id, credible.project_id)? {
             Some(project_id) => project_id,
             None if json_output => {
                 return Err(ApplyError::InvalidConfig(
-                    "`project_id` is required in credible.toml when using --json".to_string(),
+                    "`project_id` is required in credible.toml or via --project-id when using --json"
+                        .to_string(),
                 ));
             }
-            None => self.select_project(config).await?,
+            None => {
+                self.require_interactive(
+                    "Project selection",
+                    "--project-id or set `project_id` in credible.toml",
+                )?;
+                self.select_project(config).await?
+            }
         };
-        let (payload, _verification_inputs) = Self::build_payload(&credible, &root)?;
+        let options = ReleaseOptions {
+            verbose: cli_args.verbose(),
+            no_cache: self.no_cache,
+            max_source_bytes: self.max_source_bytes,
+            annotate_sources: self.annotate_sources,
+            assertion_extensions: config.assertion_extensions().to_vec(),
+            compiler_version: self.compiler_version.clone(),
+            profile: self.profile_args.profile.clone(),
+            locked: self.profile_args.locked,
+        };
+        let release =
+            crate::run_blocking(move || build_release(&credible, &root, &options)).await?;
+        for warning in &release.warnings {
+            eprintln!("{}: {warning}", "Warning".yellow().bold());
+        }
         #[cfg(feature = "credible")]
-        let verification = Self::verify_all_assertions(&_verification_inputs, json_output)?;
+        let (verification, release) = {
+            let quiet = cli_args.quiet();
+            crate::run_blocking(move || {
+                Self::verify_all_assertions(&release.deployment_bytecodes, json_output, quiet)
+                    .map(|verification| (verification, release))
+            })
+            .await?
+        };
+        let payload = release.payload;
 
-        let (http_client, base_url) = Self::build_http_client(config, &self.api_url)?;
-        let preview = Self::call_preview(&http_client, &base_url, &project_id, &payload).await?;
+        let (http_client, base_url) = Self::build_http_client(
+            config,
+            &self.api_url,
+            &self.api_version,
+            Duration::from_secs(self.api_timeout_secs),
+        )?;
+        let preview = preview_release(&http_client, &base_url, &project_id, &payload).await?;
 
         if !preview.has_changes() {
             if json_output {
@@ -137,6 +315,7 @@ impl ApplyArgs {
                         preview: Some(preview),
                         applied: false,
                         release: None,
+                        payload: self.dry_run.then(|| payload.clone()),
                     })?
                 );
             } else {
@@ -149,28 +328,43 @@ impl ApplyArgs {
             print!("{}", preview.render_plan());
         }
 
+        if self.dry_run {
+            if json_output {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&ApplyJsonOutput {
+                        status: "dry_run",
+                        project_id,
+                        #[cfg(feature = "credible")]
+                        verification,
+                        preview: Some(preview),
+                        applied: false,
+                        release: None,
+                        payload: Some(payload),
+                    })?
+                );
+            } else {
+                println!("Dry run: no release was created");
+            }
+            return Ok(());
+        }
+
         if !self.yes {
             if json_output {
                 return Err(ApplyError::JsonConfirmationRequiresYes);
             }
-            if !confirm_apply()? {
+            self.require_interactive("Confirmation", "--yes")?;
+            if !crate::run_blocking(confirm_apply).await? {
                 return Err(ApplyError::ApplyCancelled);
             }
         }
 
         let client = self.build_client(config)?;
 
-        let release = client
-            .post_projects_project_id_releases(&project_id, None, &payload)
-            .await
-            .map(dapp_api_client::generated::client::ResponseValue::into_inner)
-            .map_err(|e| {
-                ApplyError::Api {
-                    endpoint: format!("/projects/{project_id}/releases"),
-                    status: e.status().map(|s| s.as_u16()),
-                    body: e.to_string(),
-                }
-            })?;
+        let release = create_release(&client, &project_id, &payload).await?;
+        if !self.no_remember_project {
+            config.last_project = Some(project_id);
+        }
 
         if json_output {
             println!(
@@ -183,19 +377,46 @@ impl ApplyArgs {
                     preview: Some(preview),
                     applied: true,
                     release: Some(release),
+                    payload: None,
                 })?
             );
             return Ok(());
         }
 
-        Self::print_release_success(self.api_url.as_str(), &project_id, &release);
+        Self::print_release_success(
+            config.platform_url(&self.api_url).as_str(),
+            &project_id,
+            &release,
+        );
+        Ok(())
+    }
+
+    /// Rejects a prompt in non-interactive mode, naming the flag that replaces it.
+    ///
+    /// Prompts are also refused when stdin is not a terminal, so CI jobs fail
+    /// with a clear message instead of hanging or reading an empty answer.
+    fn require_interactive(
+        &self,
+        prompt: &'static str,
+        flag: &'static str,
+    ) -> Result<(), ApplyError> {
+        if self.non_interactive || !stdin().is_terminal() {
+            return Err(ApplyError::PromptRequired { prompt, flag });
+        }
         Ok(())
     }
 
     fn build_client(&self, config: &CliConfig) -> Result<GeneratedClient, ApplyError> {
-        authenticated_client(config, &self.api_url).map_err(|e| {
+        authenticated_client(
+            config,
+            &self.api_url,
+            &self.api_version,
+            Duration::from_secs(self.api_timeout_secs),
+        )
+        .map_err(|e| {
             match e {
                 crate::client::ClientBuildError::NoAuthToken => ApplyError::NoAuthToken,
+                crate::client::ClientBuildError::TokenExpired => ApplyError::TokenExpired,
                 crate::client::ClientBuildError::InvalidConfig(msg) => {
                     ApplyError::InvalidConfig(msg)
                 }
@@ -203,14 +424,40 @@ impl ApplyArgs {
         })
     }
 
+    /// Warns, or fails with `--strict`, when the token was issued by another platform.
+    fn check_token_issuer(&self, config: &CliConfig) -> Result<(), ApplyError> {
+        let target = config.platform_url(&self.api_url);
+        let Some(issuer) = config
+            .auth
+            .as_ref()
+            .and_then(|auth| auth.issuer_mismatch(&target))
+        else {
+            return Ok(());
+        };
+        if self.strict {
+            return Err(ApplyError::TokenIssuerMismatch {
+                issuer: issuer.to_string(),
+                target: target.to_string(),
+            });
+        }
+        eprintln!(
+            "{}: Logged in to {issuer} but applying to {target}; the platform will likely reject the token",
+            "Warning".yellow().bold(),
+        );
+        Ok(())
+    }
+
     fn build_http_client(
         config: &CliConfig,
         api_url: &Url,
+        api_version: &str,
+        timeout: Duration,
     ) -> Result<(reqwest::Client, String), ApplyError> {
-        let auth = config.auth.as_ref().ok_or(ApplyError::NoAuthToken)?;
-        let mut base = api_url.clone();
-        base.set_path("/api/v1");
-        let base_url = base.to_string();
+        let auth = config.auth().ok_or(ApplyError::NoAuthToken)?;
+        if auth.is_expired() {
+            return Err(ApplyError::TokenExpired);
+        }
+        let base_url = api_base_url(&config.platform_url(api_url), api_version).to_string();
 
         let mut headers = reqwest::header::HeaderMap::new();
         let auth_value = format!("Bearer {}", auth.access_token);
@@ -220,118 +467,18 @@ impl ApplyArgs {
 
         let http_client = reqwest::Client::builder()
             .default_headers(headers)
+            .timeout(timeout)
             .build()
             .map_err(|e| ApplyError::InvalidConfig(format!("Failed to build HTTP client: {e}")))?;
 
         Ok((http_client, base_url))
     }
 
-    async fn call_preview(
-        http_client: &reqwest::Client,
-        base_url: &str,
-        project_id: &Uuid,
-        payload: &PostProjectsProjectIdReleasesBody,
-    ) -> Result<PreviewResponse, ApplyError> {
-        let url = format!("{base_url}/projects/{project_id}/releases/preview");
-        let response = http_client
-            .post(&url)
-            .json(payload)
-            .send()
-            .await
-            .map_err(|e| {
-                ApplyError::Api {
-                    endpoint: format!("/projects/{project_id}/releases/preview"),
-                    status: e.status().map(|s| s.as_u16()),
-                    body: e.to_string(),
-                }
-            })?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(ApplyError::Api {
-                endpoint: format!("/projects/{project_id}/releases/preview"),
-                status: Some(status),
-                body,
-            });
-        }
-
-        response.json::<PreviewResponse>().await.map_err(|e| {
-            ApplyError::Api {
-                endpoint: format!("/projects/{project_id}/releases/preview"),
-                status: None,
-                body: format!("Failed to parse preview response: {e}"),
-            }
-        })
-    }
-
-    fn build_payload(
-        credible: &CredibleToml,
-        root: &Path,
-    ) -> Result<(PostProjectsProjectIdReleasesBody, Vec<(String, Bytes)>), ApplyError> {
-        let mut built_assertions = HashMap::new();
-        let mut payload_contracts = HashMap::new();
-        #[allow(unused_mut)]
-        let mut verification_inputs: Vec<(String, Bytes)> = Vec::new();
-
-        for (contract_key, contract) in &credible.contracts {
-            let mut assertions = Vec::with_capacity(contract.assertions.len());
-
-            for assertion in &contract.assertions {
-                let build_key = assertion.file.clone();
-                if !built_assertions.contains_key(&build_key) {
-                    let output = BuildAndFlattenArgs {
-                        root: Some(root.to_path_buf()),
-                        assertion_contract: assertion_contract_name(&assertion.file)?,
-                    }
-                    .run()
-                    .map_err(ApplyError::BuildFailed)?;
-                    built_assertions.insert(build_key.clone(), output);
-                }
-
-                let built = built_assertions.get(&build_key).ok_or_else(|| {
-                    ApplyError::InvalidConfig(format!(
-                        "Missing build output for assertion file {}",
-                        assertion.file
-                    ))
-                })?;
-
-                let contract_name = assertion_contract_name(&assertion.file)?;
-
-                #[cfg(feature = "credible")]
-                {
-                    let deployment_bytecode =
-                        build_deployment_bytecode(&built.bytecode, &built.abi, &assertion.args)
-                            .map_err(|e| ApplyError::InvalidConfig(e.to_string()))?;
-                    let display_name = format_display_name(&contract_name, &assertion.args);
-                    verification_inputs.push((display_name, deployment_bytecode));
-                }
-
-                assertions.push(build_assertion_item(assertion, built, &contract_name)?);
-            }
-
-            let contract_value = build_contract_value(contract, assertions)?;
-            payload_contracts.insert(contract_key.clone(), contract_value);
-        }
-
-        let environment = parse_field(&credible.environment, "environment")?;
-        let assertions_dir = parse_field("assertions", "assertions dir")?;
-
-        Ok((
-            PostProjectsProjectIdReleasesBody {
-                environment,
-                assertions_dir,
-                contracts: payload_contracts,
-                compiler_args: vec![],
-            },
-            verification_inputs,
-        ))
-    }
-
     #[cfg(feature = "credible")]
     fn verify_all_assertions(
         inputs: &[(String, Bytes)],
         json_output: bool,
+        quiet: bool,
     ) -> Result<VerificationSummary, ApplyError> {
         let refs: Vec<(&str, Bytes)> = inputs
             .iter()
@@ -340,7 +487,8 @@ impl ApplyArgs {
 
         let summary = run_verification(&refs);
 
-        if !json_output {
+        // In quiet mode the summary is only shown when it explains a failure
+        if !json_output && (!quiet || summary.failed > 0) {
             println!("pcl apply \u{2014} Verifying assertions...\n");
             print_verification_summary(&summary);
         }
@@ -361,26 +509,24 @@ impl ApplyArgs {
     }
 
     async fn select_project(&self, config: &CliConfig) -> Result<Uuid, ApplyError> {
-        let auth = config.auth.as_ref().ok_or(ApplyError::NoAuthToken)?;
-        let user_id = auth.user_id.as_ref().ok_or_else(|| {
+        let auth = config.auth().ok_or(ApplyError::NoAuthToken)?;
+        let user_id = auth.user_id.ok_or_else(|| {
             ApplyError::InvalidConfig(
-                "Missing user_id in auth config. Please run `pcl auth logout` then `pcl auth login` to refresh."
+                "Missing user_id in auth config. Please run `pcl auth logout` then `pcl auth login` to refresh, or set `project_id` in credible.toml when using PCL_ACCESS_TOKEN."
                     .to_string(),
             )
         })?;
 
         let client = self.build_client(config)?;
-        let projects: Vec<GetProjectsResponseItem> = client
-            .get_projects(None, Some(user_id), None)
-            .await
-            .map(dapp_api_client::generated::client::ResponseValue::into_inner)
-            .map_err(|e| {
-                ApplyError::Api {
-                    endpoint: "/projects".to_string(),
-                    status: e.status().map(|s| s.as_u16()),
-                    body: e.to_string(),
-                }
-            })?;
+        tracing::debug!(%user_id, "fetching projects");
+        let projects: Vec<GetProjectsResponseItem> = match retry_rate_limited("/projects", || {
+            client.get_projects(None, Some(&user_id), None)
+        })
+        .await
+        {
+            Ok(response) => response.into_inner(),
+            Err(e) => return Err(request_error("/projects", e).await),
+        };
 
         if projects.is_empty() {
             return Err(ApplyError::NoProjectsFound);
@@ -390,9 +536,18 @@ impl ApplyArgs {
             .iter()
             .map(|project| format!("{} ({})", *project.project_name, project.project_id))
             .collect();
-        let selected = Select::new("Select a project to apply to:", options)
-            .prompt()
-            .map_err(ApplyError::ProjectSelectionFailed)?;
+        let last_project = config.last_project.filter(|_| !self.no_remember_project);
+        let cursor = starting_cursor(
+            projects.iter().map(|project| project.project_id),
+            last_project,
+        );
+        let selected = crate::run_blocking(move || {
+            Select::new("Select a project to apply to:", options)
+                .with_starting_cursor(cursor)
+                .prompt()
+        })
+        .await
+        .map_err(ApplyError::ProjectSelectionFailed)?;
 
         projects
             .into_iter()
@@ -430,6 +585,204 @@ impl ApplyArgs {
     }
 }
 
+/// Fails if any assertion overrides its constructor signature.
+///
+/// The release payload only carries `args` and the compiled artifact, so the
+/// platform would encode the args with the compiled constructor instead.
+fn reject_constructor_signatures(credible: &CredibleToml) -> Result<(), ApplyError> {
+    for (contract_key, contract) in &credible.contracts {
+        if let Some(assertion) = contract
+            .assertions
+            .iter()
+            .find(|assertion| assertion.constructor_signature.is_some())
+        {
+            return Err(ApplyError::InvalidConfig(format!(
+                "assertion `{}` of contract `{contract_key}` sets `constructor_signature`, \
+                 which `pcl apply` cannot send to the platform; it is only supported by `pcl verify`",
+                assertion.file
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Builds every assertion in `credible` and assembles the release payload.
+///
+/// Nothing is printed; warnings are returned in [`BuiltRelease::warnings`] so
+/// embedders can surface them however they like.
+pub fn build_release(
+    credible: &CredibleToml,
+    root: &Path,
+    options: &ReleaseOptions,
+) -> Result<BuiltRelease, ApplyError> {
+    reject_constructor_signatures(credible)?;
+    let assertion_extensions = options.assertion_extensions.as_slice();
+    let mut warnings = Vec::new();
+    let mut built_assertions = HashMap::new();
+    let mut payload_contracts = HashMap::new();
+    #[allow(unused_mut)]
+    let mut verification_inputs: Vec<(String, Bytes)> = Vec::new();
+
+    for (contract_key, contract) in &credible.contracts {
+        let mut assertions = Vec::with_capacity(contract.assertions.len());
+
+        for assertion in &contract.assertions {
+            let build_key = assertion.file.clone();
+            if !built_assertions.contains_key(&build_key) {
+                tracing::debug!(assertion = %assertion.file, "building assertion");
+                let output = BuildAndFlattenArgs {
+                    root: Some(root.to_path_buf()),
+                    assertion_contract: assertion_contract_name(
+                        &assertion.file,
+                        assertion_extensions,
+                    )?,
+                    verbose: options.verbose,
+                    no_cache: options.no_cache,
+                    annotate_sources: options.annotate_sources,
+                    assertion_extensions: assertion_extensions.to_vec(),
+                    profile_args: ProfileArgs {
+                        profile: options.profile.clone(),
+                        locked: options.locked,
+                    },
+                    ..Default::default()
+                }
+                .run()
+                .map_err(ApplyError::BuildFailed)?;
+                warnings.extend(check_source_size(
+                    &assertion.file,
+                    output.flattened_source.len(),
+                    options.max_source_bytes,
+                )?);
+                built_assertions.insert(build_key.clone(), output);
+            }
+
+            let built = built_assertions.get(&build_key).ok_or_else(|| {
+                ApplyError::InvalidConfig(format!(
+                    "Missing build output for assertion file {}",
+                    assertion.file
+                ))
+            })?;
+
+            let contract_name = assertion_contract_name(&assertion.file, assertion_extensions)?;
+
+            let constructor_args_error = |source| {
+                ApplyError::ConstructorArgs {
+                    assertion: assertion.file.clone(),
+                    source,
+                }
+            };
+            let abi = &built.abi;
+            parse_constructor_args(abi, &assertion.args).map_err(constructor_args_error)?;
+
+            #[cfg(feature = "credible")]
+            {
+                let deployment_bytecode =
+                    build_deployment_bytecode(&built.bytecode, abi, &assertion.args)
+                        .map_err(|e| ApplyError::InvalidConfig(e.to_string()))?;
+                let display_name = format_display_name(&contract_name, &assertion.args);
+                verification_inputs.push((display_name, deployment_bytecode));
+            }
+
+            assertions.push(build_assertion_item(assertion, built, &contract_name)?);
+        }
+
+        let contract_value = build_contract_value(contract, assertions)?;
+        payload_contracts.insert(contract_key.clone(), contract_value);
+    }
+
+    let environment = parse_field(&credible.environment, "environment")?;
+    let assertions_dir = parse_field("assertions", "assertions dir")?;
+
+    let mut payload = PostProjectsProjectIdReleasesBody {
+        environment,
+        assertions_dir,
+        contracts: payload_contracts,
+        compiler_args: vec![],
+    };
+    if let Some(version) = &options.compiler_version {
+        override_compiler_version(&mut payload, version)?;
+    }
+
+    Ok(BuiltRelease {
+        payload,
+        deployment_bytecodes: verification_inputs,
+        warnings,
+    })
+}
+
+/// Asks the platform what applying `payload` would change, without creating a release.
+pub async fn preview_release(
+    http_client: &reqwest::Client,
+    base_url: &str,
+    project_id: &Uuid,
+    payload: &PostProjectsProjectIdReleasesBody,
+) -> Result<PreviewResponse, ApplyError> {
+    let url = format!("{base_url}/projects/{project_id}/releases/preview");
+    tracing::debug!(%url, "requesting release preview");
+    let endpoint = format!("/projects/{project_id}/releases/preview");
+    let url = &url;
+    let response = match retry_rate_limited(&endpoint, || {
+        async move {
+            let response = http_client
+                .post(url)
+                .json(payload)
+                .send()
+                .await
+                .map_err(ApiError::<()>::CommunicationError)?;
+            // Only rate limiting is retried; other statuses are handled below
+            if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                return Err(ApiError::UnexpectedResponse(response));
+            }
+            Ok(response)
+        }
+    })
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => return Err(request_error(&endpoint, e).await),
+    };
+
+    tracing::debug!(status = %response.status(), "release preview response");
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(ApplyError::NoAuthToken);
+    }
+    if !response.status().is_success() {
+        let status = response.status().as_u16();
+        let body = response.text().await.unwrap_or_default();
+        return Err(ApplyError::Api {
+            endpoint: format!("/projects/{project_id}/releases/preview"),
+            status: Some(status),
+            body: truncate_body(&body),
+        });
+    }
+
+    response.json::<PreviewResponse>().await.map_err(|e| {
+        ApplyError::Api {
+            endpoint: format!("/projects/{project_id}/releases/preview"),
+            status: None,
+            body: format!("Failed to parse preview response: {e}"),
+        }
+    })
+}
+
+/// Creates a release from `payload`, mapping failures to `ApplyError::Api`.
+pub async fn create_release(
+    client: &GeneratedClient,
+    project_id: &Uuid,
+    payload: &PostProjectsProjectIdReleasesBody,
+) -> Result<PostProjectsProjectIdReleasesResponse, ApplyError> {
+    tracing::debug!(%project_id, "creating release");
+    let endpoint = format!("/projects/{project_id}/releases");
+    match retry_rate_limited(&endpoint, || {
+        client.post_projects_project_id_releases(project_id, None, payload)
+    })
+    .await
+    {
+        Ok(response) => Ok(response.into_inner()),
+        Err(e) => Err(request_error(&endpoint, e).await),
+    }
+}
+
 /// Parse a string into a generated newtype, mapping the error to `ApplyError`.
 fn parse_field<T>(value: &str, field: &str) -> Result<T, ApplyError>
 where
@@ -441,6 +794,50 @@ where
         .map_err(|e| ApplyError::InvalidConfig(format!("Invalid {field}: {e}")))
 }
 
+/// Accepts `0.8.28`, `v0.8.28` or a full `v0.8.28+commit.<hash>` version.
+fn parse_compiler_version(value: &str) -> Result<String, String> {
+    let error = || {
+        format!(
+            "expected a compiler version like `v0.8.28` or `v0.8.28+commit.7893614a`, got `{value}`"
+        )
+    };
+    let version = value.strip_prefix('v').unwrap_or(value);
+    let (semver, build) = match version.split_once('+') {
+        Some((semver, build)) => (semver, Some(build)),
+        None => (version, None),
+    };
+    let parts: Vec<&str> = semver.split('.').collect();
+    if parts.len() != 3
+        || parts
+            .iter()
+            .any(|part| part.is_empty() || !part.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(error());
+    }
+    if let Some(build) = build {
+        let hash = build.strip_prefix("commit.").ok_or_else(error)?;
+        if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(error());
+        }
+    }
+    Ok(value.to_string())
+}
+
+/// Replaces the compiler version derived from build metadata on every assertion.
+fn override_compiler_version(
+    payload: &mut PostProjectsProjectIdReleasesBody,
+    version: &str,
+) -> Result<(), ApplyError> {
+    let version: PostProjectsProjectIdReleasesBodyContractsValueAssertionsItemCompilerVersion =
+        parse_field(version, "compiler version")?;
+    for contract in payload.contracts.values_mut() {
+        for assertion in &mut contract.assertions {
+            assertion.compiler_version = version.clone();
+        }
+    }
+    Ok(())
+}
+
 fn build_assertion_item(
     assertion: &crate::credible_config::CredibleAssertion,
     built: &pcl_phoundry::build_and_flatten::BuildAndFlatOutput,
@@ -477,6 +874,134 @@ fn build_contract_value(
     })
 }
 
+/// Converts a generated client error into an `ApplyError`.
+///
+/// Responses with undocumented status codes (gateway errors, HTML error pages)
+/// are reported with their status and a truncated body instead of a
+/// deserialization error; a 401 means the stored token is no longer accepted.
+async fn api_error<E>(endpoint: &str, err: ApiError<E>) -> ApplyError
+where
+    ApiError<E>: std::fmt::Display,
+{
+    match err {
+        ApiError::UnexpectedResponse(response) => {
+            let status = response.status();
+            if status == reqwest::StatusCode::UNAUTHORIZED {
+                return ApplyError::NoAuthToken;
+            }
+            let body = response.text().await.unwrap_or_default();
+            ApplyError::Api {
+                endpoint: endpoint.to_string(),
+                status: Some(status.as_u16()),
+                body: truncate_body(&body),
+            }
+        }
+        ApiError::InvalidResponsePayload(bytes, e) => {
+            ApplyError::Api {
+                endpoint: endpoint.to_string(),
+                status: None,
+                body: format!(
+                    "Invalid response payload ({e}): {}",
+                    truncate_body(&String::from_utf8_lossy(&bytes))
+                ),
+            }
+        }
+        other => {
+            ApplyError::Api {
+                endpoint: endpoint.to_string(),
+                status: other.status().map(|s| s.as_u16()),
+                body: other.to_string(),
+            }
+        }
+    }
+}
+
+/// Like [`api_error`], for requests sent through [`retry_rate_limited`].
+async fn request_error<E>(endpoint: &str, err: RateLimitError<E>) -> ApplyError
+where
+    ApiError<E>: std::fmt::Display,
+{
+    match err {
+        RateLimitError::RateLimited { retries } => {
+            ApplyError::RateLimited {
+                endpoint: endpoint.to_string(),
+                retries,
+            }
+        }
+        RateLimitError::Request(e) => api_error(endpoint, e).await,
+    }
+}
+
+/// Shortens a response body so error messages stay readable
+fn truncate_body(body: &str) -> String {
+    let body = body.trim();
+    if body.chars().count() > MAX_ERROR_BODY_CHARS {
+        let truncated: String = body.chars().take(MAX_ERROR_BODY_CHARS).collect();
+        format!("{truncated}... (truncated)")
+    } else {
+        body.to_string()
+    }
+}
+
+/// Rejects flattened sources over `limit` and warns about unusually large ones,
+/// so oversized uploads fail locally instead of with a server-side 413.
+fn check_source_size(
+    assertion: &str,
+    size: usize,
+    limit: Option<usize>,
+) -> Result<Option<String>, ApplyError> {
+    if let Some(limit) = limit
+        && size > limit
+    {
+        return Err(ApplyError::SourceTooLarge {
+            assertion: assertion.to_string(),
+            size,
+            limit,
+        });
+    }
+    Ok((size > SOURCE_SIZE_WARNING_BYTES).then(|| {
+        format!(
+            "flattened source for {assertion} is {size} bytes; large uploads may be rejected by the platform"
+        )
+    }))
+}
+
+/// Index of the project to highlight first: the last used one if still listed.
+fn starting_cursor(
+    mut project_ids: impl Iterator<Item = Uuid>,
+    last_project: Option<Uuid>,
+) -> usize {
+    last_project
+        .and_then(|last| project_ids.position(|id| id == last))
+        .unwrap_or(0)
+}
+
+/// Picks the project from `--project-id` or credible.toml, rejecting conflicting values.
+fn resolve_project_id(
+    flag: Option<Uuid>,
+    from_config: Option<Uuid>,
+) -> Result<Option<Uuid>, ApplyError> {
+    match (flag, from_config) {
+        (Some(flag), Some(from_config)) if flag != from_config => {
+            Err(ApplyError::InvalidConfig(format!(
+                "--project-id {flag} conflicts with project_id {from_config} in credible.toml"
+            )))
+        }
+        (flag, from_config) => Ok(flag.or(from_config)),
+    }
+}
+
+/// Re-anchors `.pcl.toml` discovery at the project root, so `--root` picks up
+/// the project's settings when pcl runs from another directory.
+fn discover_project_config(config: &mut CliConfig, root: &Path) -> Result<(), ApplyError> {
+    let current_dir = std::env::current_dir().and_then(std::fs::canonicalize).ok();
+    if current_dir.as_deref() != Some(root) {
+        config.project =
+            ProjectConfig::discover(root).map_err(|e| ApplyError::InvalidConfig(e.to_string()))?;
+    }
+    Ok(())
+}
+
 fn canonicalize_root(root: &Path) -> Result<PathBuf, ApplyError> {
     std::fs::canonicalize(root).map_err(|e| {
         ApplyError::Io {
@@ -506,3 +1031,278 @@ fn confirm_apply() -> Result<bool, ApplyError> {
         || trimmed.eq_ignore_ascii_case("y")
         || trimmed.eq_ignore_ascii_case("yes"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use mockito::{
+        Matcher,
+        Server,
+    };
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_resolve_project_id() {
+        let a = Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap();
+        let b = Uuid::parse_str("6ba7b810-9dad-11d1-80b4-00c04fd430c8").unwrap();
+
+        assert_eq!(resolve_project_id(Some(a), None).unwrap(), Some(a));
+        assert_eq!(resolve_project_id(None, Some(b)).unwrap(), Some(b));
+        assert_eq!(resolve_project_id(Some(a), Some(a)).unwrap(), Some(a));
+        assert_eq!(resolve_project_id(None, None).unwrap(), None);
+        assert!(matches!(
+            resolve_project_id(Some(a), Some(b)),
+            Err(ApplyError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_starting_cursor_prefers_last_project() {
+        let a = Uuid::from_u128(1);
+        let b = Uuid::from_u128(2);
+        let c = Uuid::from_u128(3);
+
+        assert_eq!(starting_cursor([a, b].into_iter(), Some(b)), 1);
+        assert_eq!(starting_cursor([a, b].into_iter(), Some(c)), 0);
+        assert_eq!(starting_cursor([a, b].into_iter(), None), 0);
+    }
+
+    #[test]
+    fn test_non_interactive_rejects_prompts() {
+        let args = ApplyArgs::try_parse_from(["apply", "--non-interactive"]).unwrap();
+
+        let err = args
+            .require_interactive("Confirmation", "--yes")
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            ApplyError::PromptRequired { flag: "--yes", .. }
+        ));
+        assert!(err.to_string().contains("--yes"));
+    }
+
+    #[test]
+    fn test_parse_project_id_flag() {
+        let args = ApplyArgs::try_parse_from([
+            "apply",
+            "--project-id",
+            "550e8400-e29b-41d4-a716-446655440000",
+        ])
+        .unwrap();
+        assert_eq!(
+            args.project_id,
+            Some(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap())
+        );
+    }
+
+    fn test_config(platform_url: String) -> CliConfig {
+        CliConfig {
+            platform_url: Some(platform_url),
+            auth: Some(UserAuth {
+                access_token: "test_token".to_string(),
+                expires_at: chrono::Utc::now() + chrono::Duration::hours(1),
+                user_id: Some(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_select_project_fails_fast_on_expired_token() {
+        // No server is listening; an expired token must be rejected before any request
+        let mut config = test_config("http://127.0.0.1:9".to_string());
+        if let Some(auth) = config.auth.as_mut() {
+            auth.expires_at = chrono::Utc::now() - chrono::Duration::minutes(5);
+        }
+        let args = ApplyArgs::try_parse_from(["apply"]).unwrap();
+
+        let result = args.select_project(&config).await;
+
+        assert!(matches!(result, Err(ApplyError::TokenExpired)));
+    }
+
+    #[tokio::test]
+    async fn test_select_project_reports_html_gateway_error() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects")
+            .match_query(Matcher::Any)
+            .with_status(502)
+            .with_header("content-type", "text/html")
+            .with_body("<html><body><h1>502 Bad Gateway</h1></body></html>")
+            .create_async()
+            .await;
+
+        let args = ApplyArgs::try_parse_from(["apply"]).unwrap();
+        let result = args.select_project(&test_config(server.url())).await;
+
+        match result {
+            Err(ApplyError::Api { status, body, .. }) => {
+                assert_eq!(status, Some(502));
+                assert!(body.contains("502 Bad Gateway"));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn test_select_project_gives_up_when_rate_limited() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects")
+            .match_query(Matcher::Any)
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .expect(crate::client::MAX_RATE_LIMIT_RETRIES as usize + 1)
+            .create_async()
+            .await;
+
+        let args = ApplyArgs::try_parse_from(["apply"]).unwrap();
+        let result = args.select_project(&test_config(server.url())).await;
+
+        match result {
+            Err(ApplyError::RateLimited { endpoint, retries }) => {
+                assert_eq!(endpoint, "/projects");
+                assert_eq!(retries, crate::client::MAX_RATE_LIMIT_RETRIES);
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn test_truncate_body() {
+        assert_eq!(truncate_body("  short  "), "short");
+        let long = "x".repeat(MAX_ERROR_BODY_CHARS + 10);
+        assert!(truncate_body(&long).ends_with("... (truncated)"));
+    }
+
+    #[test]
+    fn test_discover_project_config_from_root() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join(".pcl.toml"),
+            "platform_url = \"https://project.example\"\n",
+        )
+        .unwrap();
+        let root = canonicalize_root(temp_dir.path()).unwrap();
+
+        let mut config = CliConfig::default();
+        discover_project_config(&mut config, &root).unwrap();
+        assert_eq!(
+            config
+                .platform_url(&DEFAULT_PLATFORM_URL.parse().unwrap())
+                .as_str(),
+            "https://project.example/"
+        );
+    }
+
+    #[test]
+    fn test_parse_compiler_version() {
+        for valid in ["0.8.28", "v0.8.28", "v0.8.28+commit.7893614a"] {
+            assert_eq!(parse_compiler_version(valid).as_deref(), Ok(valid));
+        }
+        for invalid in [
+            "",
+            "v0.8",
+            "0.8.x",
+            "v0.8.28+7893614a",
+            "v0.8.28+commit.xyz",
+        ] {
+            assert!(parse_compiler_version(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_dry_run_conflicts_with_yes() {
+        let args = ApplyArgs::try_parse_from(["apply", "--dry-run"]).unwrap();
+        assert!(args.dry_run);
+        assert!(ApplyArgs::try_parse_from(["apply", "--dry-run", "--yes"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_profile_flag() {
+        let args = ApplyArgs::try_parse_from(["apply", "--profile", "assertions"]).unwrap();
+        assert_eq!(args.profile_args.profile.as_deref(), Some("assertions"));
+        assert!(!args.profile_args.locked);
+        let args = ApplyArgs::try_parse_from(["apply", "--locked"]).unwrap();
+        assert!(args.profile_args.locked);
+    }
+
+    #[test]
+    fn test_build_release_rejects_constructor_signature() {
+        let credible: CredibleToml = toml::from_str(
+            r#"
+environment = "production"
+
+[contracts.vault]
+address = "0x0000000000000000000000000000000000000001"
+name = "Vault"
+
+[[contracts.vault.assertions]]
+file = "VaultAssertion.a.sol"
+args = ["0x0000000000000000000000000000000000000001"]
+constructor_signature = "constructor(address)"
+"#,
+        )
+        .unwrap();
+
+        // Rejected before anything is built, so the root does not need to exist
+        let err = build_release(
+            &credible,
+            Path::new("/nonexistent"),
+            &ReleaseOptions::default(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, ApplyError::InvalidConfig(msg)
+                if msg.contains("constructor_signature") && msg.contains("pcl verify")),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_check_source_size() {
+        assert!(matches!(check_source_size("A.a.sol", 100, None), Ok(None)));
+        assert!(matches!(
+            check_source_size("A.a.sol", 100, Some(100)),
+            Ok(None)
+        ));
+        let warning = check_source_size("A.a.sol", SOURCE_SIZE_WARNING_BYTES + 1, None)
+            .unwrap()
+            .unwrap();
+        assert!(warning.contains("A.a.sol"));
+        assert!(matches!(
+            check_source_size("A.a.sol", 101, Some(100)),
+            Err(ApplyError::SourceTooLarge {
+                size: 101,
+                limit: 100,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_select_project_uses_configured_platform_url() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v1/projects")
+            .match_query(Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body("[]")
+            .create_async()
+            .await;
+
+        let args = ApplyArgs::try_parse_from(["apply"]).unwrap();
+
+        let result = args.select_project(&test_config(server.url())).await;
+
+        assert!(matches!(result, Err(ApplyError::NoProjectsFound)));
+        mock.assert_async().await;
+    }
+}
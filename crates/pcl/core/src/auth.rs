@@ -1,5 +1,10 @@
 use crate::{
+    DEFAULT_API_VERSION,
     DEFAULT_PLATFORM_URL,
+    client::{
+        api_base_url,
+        parse_api_version,
+    },
     config::{
         CliConfig,
         UserAuth,
@@ -20,15 +25,29 @@ use indicatif::{
     ProgressBar,
     ProgressStyle,
 };
+use pcl_common::args::CliArgs;
+use serde_json::json;
+use std::{
+    hash::{
+        BuildHasher,
+        RandomState,
+    },
+    io::IsTerminal,
+};
 use tokio::time::{
     Duration,
+    Instant,
     sleep,
 };
 
-/// Interval between authentication status checks
-const POLL_INTERVAL: Duration = Duration::from_secs(2);
-/// Maximum number of retry attempts (5 minutes worth of 2-second intervals)
-const MAX_RETRIES: u32 = 150;
+/// Default interval between authentication status checks, in seconds
+const DEFAULT_POLL_INTERVAL_SECS: u64 = 2;
+/// Default time to wait for the user to authenticate, in seconds
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+/// Upper bound for the backoff between authentication status checks
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(10);
+/// How many times the user may extend a timed-out login with the same code
+const MAX_TIMEOUT_EXTENSIONS: u32 = 2;
 
 /// Authentication commands for the PCL CLI
 #[derive(clap::Parser)]
@@ -45,6 +64,44 @@ pub struct AuthCommand {
         help = "Base URL for authentication service"
     )]
     pub auth_url: url::Url,
+
+    #[arg(
+        long = "api-version",
+        env = "PCL_API_VERSION",
+        default_value = DEFAULT_API_VERSION,
+        value_parser = parse_api_version,
+        help = "Platform API version to use (e.g. v1)"
+    )]
+    pub api_version: String,
+
+    #[arg(
+        long = "poll-interval-secs",
+        default_value_t = DEFAULT_POLL_INTERVAL_SECS,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Initial seconds between authentication status checks; backs off up to 10s"
+    )]
+    pub poll_interval_secs: u64,
+
+    #[arg(
+        long = "timeout-secs",
+        default_value_t = DEFAULT_TIMEOUT_SECS,
+        value_parser = clap::value_parser!(u64).range(1..=3600),
+        help = "Seconds to wait for authentication before giving up (max 3600)"
+    )]
+    pub timeout_secs: u64,
+
+    #[arg(
+        long,
+        help = "Fail on timeout instead of offering to keep waiting; implied when stdin is not a terminal"
+    )]
+    pub non_interactive: bool,
+
+    #[arg(
+        long,
+        env = "PCL_NO_BROWSER",
+        help = "Only print the login URL and code instead of opening a browser, e.g. over SSH"
+    )]
+    pub no_browser: bool,
 }
 
 /// Available authentication subcommands
@@ -71,13 +128,31 @@ pub enum AuthSubcommands {
         after_help = "Example: pcl auth status"
     )]
     Status,
+
+    /// Print the connected identity only
+    #[command(
+        long_about = "Prints the connected wallet address (or email / user id when no wallet is linked) with no decoration. Exits non-zero when not logged in.",
+        after_help = "Example: pcl auth whoami"
+    )]
+    Whoami,
 }
 
 impl AuthCommand {
+    /// Interval between authentication status checks
+    fn poll_interval(&self) -> Duration {
+        Duration::from_secs(self.poll_interval_secs)
+    }
+
+    /// Number of status checks that fit in the configured timeout, at least one
+    fn max_attempts(&self) -> u32 {
+        let attempts = self.timeout_secs.div_ceil(self.poll_interval_secs.max(1));
+        u32::try_from(attempts).unwrap_or(u32::MAX).max(1)
+    }
+
     /// Execute the authentication command
-    pub async fn run(&self, config: &mut CliConfig) -> Result<(), AuthError> {
+    pub async fn run(&self, cli_args: &CliArgs, config: &mut CliConfig) -> Result<(), AuthError> {
         match &self.command {
-            AuthSubcommands::Login => self.login(config).await,
+            AuthSubcommands::Login => self.login(config, cli_args.quiet()).await,
             AuthSubcommands::Logout => {
                 Self::logout(config);
                 Ok(())
@@ -86,11 +161,12 @@ impl AuthCommand {
                 Self::status(config);
                 Ok(())
             }
+            AuthSubcommands::Whoami => Self::whoami(config, cli_args.json_output()),
         }
     }
 
     /// Initiate the login process and wait for user authentication
-    async fn login(&self, config: &mut CliConfig) -> Result<(), AuthError> {
+    async fn login(&self, config: &mut CliConfig, quiet: bool) -> Result<(), AuthError> {
         if let Some(auth) = &config.auth {
             println!(
                 "{} Already logged in as: {}",
@@ -104,18 +180,19 @@ impl AuthCommand {
             return Ok(());
         }
 
-        let client = self.api_client();
+        let auth_url = config.platform_url(&self.auth_url);
+        let client = Self::api_client(&auth_url, &self.api_version);
         let auth_response = Self::request_auth_code(&client).await?;
-        self.display_login_instructions(&auth_response);
-        self.wait_for_verification(config, &client, &auth_response)
+        // Opening a browser only helps when someone is sitting at this machine
+        let open_browser = !self.no_browser && std::io::stdout().is_terminal();
+        Self::display_login_instructions(&auth_url, &auth_response, open_browser);
+        self.wait_for_verification(config, &client, &auth_response, quiet)
             .await
     }
 
     // Helper to create a new API client with the base URL set
-    fn api_client(&self) -> GeneratedClient {
-        let mut base = self.auth_url.clone();
-        base.set_path("/api/v1");
-        GeneratedClient::new(base.as_str())
+    fn api_client(auth_url: &url::Url, api_version: &str) -> GeneratedClient {
+        GeneratedClient::new(api_base_url(auth_url, api_version).as_str())
     }
 
     /// Request an authentication code from the server
@@ -129,16 +206,20 @@ impl AuthCommand {
             .map_err(|e| AuthError::AuthRequestFailed(e.to_string()))
     }
 
-    /// Display login URL and code to the user, attempting to open the browser automatically
-    fn display_login_instructions(&self, auth_response: &GetCliAuthCodeResponse) {
-        let mut device_url = self.auth_url.clone();
+    /// Display login URL and code to the user, opening the browser automatically if `open_browser`
+    fn display_login_instructions(
+        auth_url: &url::Url,
+        auth_response: &GetCliAuthCodeResponse,
+        open_browser: bool,
+    ) {
+        let mut device_url = auth_url.clone();
         device_url.set_path("/device");
         device_url
             .query_pairs_mut()
             .append_pair("session_id", &auth_response.session_id.to_string());
         let url = device_url.as_str();
 
-        if open::that(url).is_ok() {
+        if open_browser && open::that(url).is_ok() {
             println!(
                 "\n{} Opening browser for authentication...\n\n🔗 {}\n📝 {}\n",
                 "🌐".green(),
@@ -160,8 +241,13 @@ impl AuthCommand {
         config: &mut CliConfig,
         client: &GeneratedClient,
         auth_response: &GetCliAuthCodeResponse,
+        quiet: bool,
     ) -> Result<(), AuthError> {
-        let spinner = ProgressBar::new_spinner();
+        let spinner = if quiet {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new_spinner()
+        };
         spinner.set_style(
             ProgressStyle::default_spinner()
                 .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏")
@@ -172,62 +258,120 @@ impl AuthCommand {
         );
         spinner.enable_steady_tick(Duration::from_millis(80));
         spinner.set_message("Waiting for authentication...");
+        pcl_common::spinner::track(&spinner);
+
+        let max_attempts = self.max_attempts();
+        let mut attempts = 0;
+        let mut extensions = 0;
+        loop {
+            let deadline = Instant::now() + Duration::from_secs(self.timeout_secs);
+            let window_start = attempts;
+            while attempts - window_start < max_attempts {
+                // Stop polling once the session has expired
+                if chrono::Utc::now() >= auth_response.expires_at {
+                    spinner.finish_with_message("❌ Session expired");
+                    return Err(AuthError::SessionExpired);
+                }
 
-        for _ in 0..MAX_RETRIES {
-            // Stop polling once the session has expired
-            if chrono::Utc::now() >= auth_response.expires_at {
-                spinner.finish_with_message("❌ Session expired");
-                return Err(AuthError::SessionExpired);
-            }
-
-            let status = match Self::check_auth_status(client, auth_response).await {
-                Ok(s) => s,
-                // Transient errors — keep polling
-                Err(AuthError::ServerError(_) | AuthError::StatusRequestFailed(_)) => {
-                    spinner.tick();
-                    sleep(POLL_INTERVAL).await;
-                    continue;
+                tracing::trace!(session_id = %auth_response.session_id, "polling auth status");
+                attempts += 1;
+                let status = match Self::check_auth_status(client, auth_response).await {
+                    Ok(s) => s,
+                    // Transient errors — keep polling
+                    Err(e @ (AuthError::ServerError(_) | AuthError::StatusRequestFailed(_))) => {
+                        tracing::debug!(error = %e, "transient auth status error, retrying");
+                        if !self.wait_before_retry(attempts, deadline).await {
+                            break;
+                        }
+                        continue;
+                    }
+                    // Terminal errors — stop immediately
+                    Err(e) => {
+                        spinner.finish_with_message(format!("❌ {e}"));
+                        return Err(e);
+                    }
+                };
+
+                if status.verified {
+                    let token = status.token.ok_or_else(|| {
+                        AuthError::InvalidAuthData("Verified but missing access token".to_string())
+                    })?;
+                    let refresh_token = status.refresh_token.ok_or_else(|| {
+                        AuthError::InvalidAuthData("Verified but missing refresh token".to_string())
+                    })?;
+                    let user_id = status.user_id.ok_or_else(|| {
+                        AuthError::InvalidAuthData("Verified but missing user_id".to_string())
+                    })?;
+                    let wallet_address = status
+                        .address
+                        .and_then(|a| a.to_string().parse::<Address>().ok());
+
+                    spinner.finish_with_message("✅ Authentication successful!");
+                    let platform_url = config.platform_url(&self.auth_url).to_string();
+                    config.auth = Some(UserAuth {
+                        access_token: token,
+                        refresh_token,
+                        expires_at: auth_response.expires_at,
+                        user_id: Some(user_id),
+                        wallet_address,
+                        email: status.email,
+                        platform_url: Some(platform_url),
+                    });
+                    Self::display_success_message(config, quiet)?;
+                    return Ok(());
                 }
-                // Terminal errors — stop immediately
-                Err(e) => {
-                    spinner.finish_with_message(format!("❌ {e}"));
-                    return Err(e);
+
+                if !self.wait_before_retry(attempts, deadline).await {
+                    break;
                 }
-            };
-
-            if status.verified {
-                let token = status.token.ok_or_else(|| {
-                    AuthError::InvalidAuthData("Verified but missing access token".to_string())
-                })?;
-                let refresh_token = status.refresh_token.ok_or_else(|| {
-                    AuthError::InvalidAuthData("Verified but missing refresh token".to_string())
-                })?;
-                let user_id = status.user_id.ok_or_else(|| {
-                    AuthError::InvalidAuthData("Verified but missing user_id".to_string())
-                })?;
-                let wallet_address = status
-                    .address
-                    .and_then(|a| a.to_string().parse::<Address>().ok());
-
-                spinner.finish_with_message("✅ Authentication successful!");
-                config.auth = Some(UserAuth {
-                    access_token: token,
-                    refresh_token,
-                    expires_at: auth_response.expires_at,
-                    user_id: Some(user_id),
-                    wallet_address,
-                    email: status.email,
-                });
-                Self::display_success_message(config)?;
-                return Ok(());
             }
 
-            spinner.tick();
-            sleep(POLL_INTERVAL).await;
+            // The code stays valid until the session expires, so keep polling with it
+            if extensions < MAX_TIMEOUT_EXTENSIONS && self.confirm_extension(&spinner).await? {
+                extensions += 1;
+                tracing::debug!(extensions, "extending authentication wait");
+                continue;
+            }
+            spinner.finish_with_message("❌ Authentication timed out");
+            return Err(AuthError::Timeout(attempts));
+        }
+    }
+
+    /// Asks whether to keep waiting after a timeout.
+    ///
+    /// Never prompts in non-interactive mode or without a terminal on stdin.
+    async fn confirm_extension(&self, spinner: &ProgressBar) -> Result<bool, AuthError> {
+        if self.non_interactive || !std::io::stdin().is_terminal() {
+            return Ok(false);
         }
+        let spinner = spinner.clone();
+        let message = format!(
+            "Authentication is still pending. Keep waiting for another {}s?",
+            self.timeout_secs
+        );
+        crate::run_blocking(move || {
+            spinner.suspend(|| inquire::Confirm::new(&message).with_default(true).prompt())
+        })
+        .await
+        .map_err(AuthError::PromptFailed)
+    }
 
-        spinner.finish_with_message("❌ Authentication timed out");
-        Err(AuthError::Timeout(MAX_RETRIES))
+    /// Sleeps before the next status check, never past `deadline`.
+    ///
+    /// Returns `false` once the timeout budget is used up.
+    async fn wait_before_retry(&self, attempts: u32, deadline: Instant) -> bool {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return false;
+        }
+        let jitter = RandomState::new().hash_one(attempts);
+        let delay = backoff_delay(self.poll_interval(), attempts.saturating_sub(1), jitter);
+        tracing::trace!(
+            delay_ms = delay.as_millis(),
+            "waiting before next auth status check"
+        );
+        sleep(delay.min(remaining)).await;
+        true
     }
 
     /// Check authentication status using the generated client.
@@ -243,11 +387,15 @@ impl AuthCommand {
     }
 
     /// Display success message after authentication
-    fn display_success_message(config: &CliConfig) -> Result<(), AuthError> {
+    fn display_success_message(config: &CliConfig, quiet: bool) -> Result<(), AuthError> {
         let auth = config
             .auth
             .as_ref()
             .ok_or_else(|| AuthError::InvalidAuthData("Missing auth after update".to_string()))?;
+        if quiet {
+            println!("Connected as: {}", auth.display_name());
+            return Ok(());
+        }
         println!(
             "{}\n🔗 {}\n",
             "Authentication successful! 🎉".green().bold(),
@@ -274,6 +422,42 @@ impl AuthCommand {
         };
         println!("{icon} {message}");
     }
+
+    /// Print the connected identity without decoration, for use in scripts
+    fn whoami(config: &CliConfig, json_output: bool) -> Result<(), AuthError> {
+        let auth = config.auth.as_ref().ok_or(AuthError::NotAuthenticated)?;
+        println!("{}", Self::whoami_output(auth, json_output));
+        Ok(())
+    }
+
+    /// The line printed by `pcl auth whoami`; the zero address counts as no wallet
+    fn whoami_output(auth: &UserAuth, json_output: bool) -> String {
+        if json_output {
+            json!({
+                "address": auth.wallet_address.filter(|addr| *addr != Address::ZERO),
+                "email": auth.email,
+                "user_id": auth.user_id,
+            })
+            .to_string()
+        } else {
+            auth.display_name()
+        }
+    }
+}
+
+/// Capped exponential backoff with jitter between status checks.
+///
+/// The un-jittered delay doubles from `base` on every retry up to
+/// [`MAX_POLL_INTERVAL`]; the actual delay is drawn between half of that and
+/// the full value (but never below `base`) so concurrent CLIs spread out.
+fn backoff_delay(base: Duration, retry: u32, jitter: u64) -> Duration {
+    let cap = MAX_POLL_INTERVAL.max(base);
+    let delay = base
+        .saturating_mul(2u32.saturating_pow(retry.min(16)))
+        .min(cap);
+    let floor = (delay / 2).max(base);
+    let spread = u64::try_from((delay - floor).as_millis()).unwrap_or(u64::MAX);
+    floor + Duration::from_millis(jitter % spread.saturating_add(1))
 }
 
 #[cfg(test)]
@@ -300,7 +484,9 @@ mod tests {
                         .unwrap(),
                 ),
                 email: None,
+                platform_url: None,
             }),
+            ..Default::default()
         }
     }
 
@@ -313,16 +499,75 @@ mod tests {
         let cmd = AuthCommand {
             command: AuthSubcommands::Login,
             auth_url: "https://app.phylax.systems".parse().unwrap(),
+            api_version: DEFAULT_API_VERSION.to_string(),
+            non_interactive: false,
+            no_browser: true,
+            poll_interval_secs: DEFAULT_POLL_INTERVAL_SECS,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
         };
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
-        cmd.display_login_instructions(&auth_response);
+        AuthCommand::display_login_instructions(&cmd.auth_url, &auth_response, !cmd.no_browser);
+    }
+
+    #[test]
+    fn test_backoff_delay_grows_and_is_capped() {
+        let base = Duration::from_secs(2);
+
+        assert_eq!(backoff_delay(base, 0, 12345), base);
+        for jitter in [0, 1, 999, u64::MAX] {
+            let second = backoff_delay(base, 1, jitter);
+            assert!(second >= base && second <= Duration::from_secs(4));
+
+            let late = backoff_delay(base, 20, jitter);
+            assert!(late >= Duration::from_secs(5) && late <= MAX_POLL_INTERVAL);
+        }
+
+        // A poll interval above the cap is used as-is
+        let slow = Duration::from_secs(30);
+        assert_eq!(backoff_delay(slow, 3, 42), slow);
+    }
+
+    #[test]
+    fn test_max_attempts_from_flags() {
+        let cmd = AuthCommand::try_parse_from(vec!["auth", "login"]).unwrap();
+        assert_eq!(cmd.max_attempts(), 150);
+
+        let cmd = AuthCommand::try_parse_from(vec![
+            "auth",
+            "--poll-interval-secs",
+            "5",
+            "--timeout-secs",
+            "12",
+            "login",
+        ])
+        .unwrap();
+        assert_eq!(cmd.poll_interval(), Duration::from_secs(5));
+        assert_eq!(cmd.max_attempts(), 3);
+    }
+
+    #[test]
+    fn test_poll_flags_are_validated() {
+        assert!(
+            AuthCommand::try_parse_from(vec!["auth", "--poll-interval-secs", "0", "login"])
+                .is_err()
+        );
+        assert!(
+            AuthCommand::try_parse_from(vec!["auth", "--timeout-secs", "86400", "login"]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_parses_no_browser_flag() {
+        let cmd = AuthCommand::try_parse_from(vec!["auth", "--no-browser", "login"]).unwrap();
+        assert!(cmd.no_browser);
     }
 
     #[test]
     fn test_display_success_message() {
         let config = create_test_config();
-        AuthCommand::display_success_message(&config).unwrap();
+        AuthCommand::display_success_message(&config, false).unwrap();
+        AuthCommand::display_success_message(&config, true).unwrap();
     }
 
     #[tokio::test]
@@ -339,7 +584,7 @@ mod tests {
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
 
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let result = AuthCommand::request_auth_code(&client).await;
 
         assert!(result.is_ok());
@@ -366,7 +611,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
 
@@ -404,7 +649,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
 
@@ -434,7 +679,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
 
@@ -466,6 +711,52 @@ mod tests {
         AuthCommand::status(&config);
     }
 
+    #[test]
+    fn test_whoami() {
+        let config = create_test_config();
+        assert!(AuthCommand::whoami(&config, false).is_ok());
+        let auth = config.auth.unwrap();
+
+        assert_eq!(
+            AuthCommand::whoami_output(&auth, false),
+            "0x1234567890123456789012345678901234567890"
+        );
+        let output: serde_json::Value =
+            serde_json::from_str(&AuthCommand::whoami_output(&auth, true)).unwrap();
+        assert_eq!(
+            output["address"],
+            "0x1234567890123456789012345678901234567890"
+        );
+        assert!(output["email"].is_null());
+        assert_eq!(output["user_id"], "550e8400-e29b-41d4-a716-446655440000");
+    }
+
+    #[test]
+    fn test_whoami_falls_back_without_wallet() {
+        let mut auth = create_test_config().auth.unwrap();
+        auth.wallet_address = Some(Address::ZERO);
+        auth.email = Some("dev@example.com".to_string());
+
+        assert_eq!(AuthCommand::whoami_output(&auth, false), "dev@example.com");
+        let output: serde_json::Value =
+            serde_json::from_str(&AuthCommand::whoami_output(&auth, true)).unwrap();
+        assert!(output["address"].is_null());
+        assert_eq!(output["email"], "dev@example.com");
+
+        auth.email = None;
+        assert_eq!(
+            AuthCommand::whoami_output(&auth, false),
+            "550e8400-e29b-41d4-a716-446655440000"
+        );
+    }
+
+    #[test]
+    fn test_whoami_when_logged_out() {
+        let config = CliConfig::default();
+        let result = AuthCommand::whoami(&config, false);
+        assert!(matches!(result, Err(AuthError::NotAuthenticated)));
+    }
+
     #[tokio::test]
     async fn test_login_when_already_authenticated() {
         let mut config = create_test_config();
@@ -511,7 +802,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
 
@@ -533,7 +824,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let mut config = CliConfig::default();
 
         // Build an auth response with expiresAt in the past
@@ -543,7 +834,7 @@ mod tests {
         .unwrap();
 
         let result = cmd
-            .wait_for_verification(&mut config, &client, &expired_response)
+            .wait_for_verification(&mut config, &client, &expired_response, false)
             .await;
 
         assert!(result.is_err());
@@ -578,7 +869,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
 
@@ -611,7 +902,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
 
@@ -644,7 +935,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
 
@@ -677,7 +968,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
 
@@ -726,7 +1017,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let mut config = CliConfig::default();
 
         // Use a far-future expiresAt so the client-side check doesn't trigger
@@ -736,7 +1027,7 @@ mod tests {
         .unwrap();
 
         let result = cmd
-            .wait_for_verification(&mut config, &client, &auth_response)
+            .wait_for_verification(&mut config, &client, &auth_response, false)
             .await;
 
         assert!(
@@ -785,7 +1076,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let mut config = CliConfig::default();
 
         let auth_response: GetCliAuthCodeResponse = serde_json::from_str(
@@ -794,7 +1085,7 @@ mod tests {
         .unwrap();
 
         let result = cmd
-            .wait_for_verification(&mut config, &client, &auth_response)
+            .wait_for_verification(&mut config, &client, &auth_response, false)
             .await;
 
         assert!(
@@ -826,7 +1117,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let auth_response: GetCliAuthCodeResponse =
             serde_json::from_str(test_auth_response_json()).unwrap();
 
@@ -858,7 +1149,7 @@ mod tests {
 
         let cmd = AuthCommand::try_parse_from(vec!["auth", "--auth-url", &server.url(), "login"])
             .unwrap();
-        let client = cmd.api_client();
+        let client = AuthCommand::api_client(&cmd.auth_url, &cmd.api_version);
         let mut config = CliConfig::default();
 
         let auth_response: GetCliAuthCodeResponse = serde_json::from_str(
@@ -867,7 +1158,7 @@ mod tests {
         .unwrap();
 
         let result = cmd
-            .wait_for_verification(&mut config, &client, &auth_response)
+            .wait_for_verification(&mut config, &client, &auth_response, false)
             .await;
 
         assert!(
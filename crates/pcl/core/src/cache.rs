@@ -0,0 +1,140 @@
+//! `pcl cache`: inspect and clear locally cached build artifacts.
+//!
+//! Only the flatten cache lives here; configuration and credentials are
+//! managed by `pcl config` and are never touched.
+
+use clap::Parser;
+use pcl_common::args::CliArgs;
+use pcl_phoundry::flatten_cache::{
+    self,
+    CacheStats,
+};
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Command-line arguments for cache management
+#[derive(Parser, Debug)]
+#[command(about = "Inspect and clear cached flattened sources")]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    command: CacheCommand,
+}
+
+/// Subcommands for cache management
+#[derive(clap::Subcommand, Debug)]
+enum CacheCommand {
+    #[command(about = "Show the cache location, entry count and size")]
+    Info {
+        /// Print the cache information as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    #[command(
+        about = "Remove all cached flattened sources",
+        after_help = "Example: pcl cache clear"
+    )]
+    Clear,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    #[error("Could not determine the cache directory. Set XDG_CACHE_HOME.")]
+    NoCacheDirectory,
+
+    #[error("Failed to access cache directory {0:?}: {1}")]
+    Io(PathBuf, #[source] std::io::Error),
+
+    #[error("Failed to encode JSON output: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Debug, Serialize)]
+struct CacheInfo {
+    path: PathBuf,
+    #[serde(flatten)]
+    stats: CacheStats,
+}
+
+impl CacheArgs {
+    /// Executes the cache command
+    pub fn run(&self, cli_args: &CliArgs) -> Result<(), CacheError> {
+        let dir = flatten_cache::flatten_dir().ok_or(CacheError::NoCacheDirectory)?;
+        match &self.command {
+            CacheCommand::Info { json } => {
+                let stats =
+                    flatten_cache::stats(&dir).map_err(|e| CacheError::Io(dir.clone(), e))?;
+                if *json || cli_args.json_output() {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&CacheInfo { path: dir, stats })?
+                    );
+                } else {
+                    println!("Cache path: {}", dir.display());
+                    println!("Entries: {}", stats.entries);
+                    println!("Size: {}", format_bytes(stats.bytes));
+                }
+            }
+            CacheCommand::Clear => {
+                let removed =
+                    flatten_cache::clear(&dir).map_err(|e| CacheError::Io(dir.clone(), e))?;
+                if cli_args.json_output() {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&CacheInfo {
+                            path: dir,
+                            stats: removed,
+                        })?
+                    );
+                } else if !cli_args.quiet() {
+                    eprintln!(
+                        "Removed {} cached source{} ({})",
+                        removed.entries,
+                        if removed.entries == 1 { "" } else { "s" },
+                        format_bytes(removed.bytes)
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 KiB`.
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+    format!("{value:.1} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn test_parses_cache_commands() {
+        let args = CacheArgs::try_parse_from(["cache", "info", "--json"]).unwrap();
+        assert!(matches!(args.command, CacheCommand::Info { json: true }));
+        let args = CacheArgs::try_parse_from(["cache", "clear"]).unwrap();
+        assert!(matches!(args.command, CacheCommand::Clear));
+    }
+}
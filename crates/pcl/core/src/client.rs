@@ -1,23 +1,144 @@
 use crate::config::CliConfig;
-use dapp_api_client::generated::client::Client as GeneratedClient;
+use dapp_api_client::generated::client::{
+    Client as GeneratedClient,
+    Error as ApiError,
+};
+use reqwest::{
+    StatusCode,
+    header::{
+        HeaderMap,
+        RETRY_AFTER,
+    },
+};
+use std::time::Duration;
+
+/// Number of times a rate-limited (HTTP 429) request is retried before giving up
+pub const MAX_RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Wait used when a 429 response has no usable `Retry-After` header
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(2);
+
+/// Upper bound on a single `Retry-After` wait, so a bad header cannot hang the CLI
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
 
 #[derive(Debug, thiserror::Error)]
 pub enum ClientBuildError {
     #[error("Run `pcl auth login` first")]
     NoAuthToken,
 
+    #[error("Authentication token expired. Run `pcl auth login` again.")]
+    TokenExpired,
+
     #[error("Invalid config: {0}")]
     InvalidConfig(String),
 }
 
+/// Returns the versioned API base URL, `{platform_url}/api/{api_version}`.
+pub fn api_base_url(platform_url: &url::Url, api_version: &str) -> url::Url {
+    let mut base = platform_url.clone();
+    base.set_path(&format!("/api/{api_version}"));
+    base
+}
+
+/// Parses an `--api-version` value such as `v1` or `v2`.
+pub fn parse_api_version(value: &str) -> Result<String, String> {
+    match value.strip_prefix('v') {
+        Some(number) if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) => {
+            Ok(value.to_string())
+        }
+        _ => Err(format!("expected an API version like `v1`, got `{value}`")),
+    }
+}
+
+/// Failure of a request sent through [`retry_rate_limited`].
+#[derive(Debug)]
+pub enum RateLimitError<E> {
+    /// The platform still answered 429 after `retries` retries
+    RateLimited { retries: u32 },
+    /// Any other failure, returned as soon as it happens
+    Request(ApiError<E>),
+}
+
+/// Sends a request, waiting out HTTP 429 responses.
+///
+/// The wait honors the `Retry-After` header and is reported on stderr. After
+/// [`MAX_RATE_LIMIT_RETRIES`] retries the request fails with
+/// [`RateLimitError::RateLimited`].
+pub async fn retry_rate_limited<T, E, F, Fut>(
+    endpoint: &str,
+    mut send: F,
+) -> Result<T, RateLimitError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ApiError<E>>>,
+{
+    let mut retries = 0;
+    loop {
+        let err = match send().await {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        let Some(delay) = rate_limit_delay(&err) else {
+            return Err(RateLimitError::Request(err));
+        };
+        if retries == MAX_RATE_LIMIT_RETRIES {
+            return Err(RateLimitError::RateLimited { retries });
+        }
+        retries += 1;
+        eprintln!(
+            "Rate limited by {endpoint}; retrying in {}s ({retries}/{MAX_RATE_LIMIT_RETRIES})",
+            delay.as_secs()
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Returns how long to wait before retrying, or `None` if `err` is not a 429.
+fn rate_limit_delay<E>(err: &ApiError<E>) -> Option<Duration> {
+    let headers = match err {
+        ApiError::ErrorResponse(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+            response.headers()
+        }
+        ApiError::UnexpectedResponse(response)
+            if response.status() == StatusCode::TOO_MANY_REQUESTS =>
+        {
+            response.headers()
+        }
+        _ => return None,
+    };
+    Some(retry_after(headers))
+}
+
+/// Parses `Retry-After` as delay-seconds or an HTTP date, capped at [`MAX_RETRY_AFTER`].
+fn retry_after(headers: &HeaderMap) -> Duration {
+    let Some(value) = headers.get(RETRY_AFTER).and_then(|v| v.to_str().ok()) else {
+        return DEFAULT_RETRY_AFTER;
+    };
+    let value = value.trim();
+    let delay = if let Ok(seconds) = value.parse::<u64>() {
+        Duration::from_secs(seconds)
+    } else if let Ok(date) = chrono::DateTime::parse_from_rfc2822(value) {
+        (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+            .to_std()
+            .unwrap_or(Duration::ZERO)
+    } else {
+        DEFAULT_RETRY_AFTER
+    };
+    delay.min(MAX_RETRY_AFTER)
+}
+
 pub fn authenticated_client(
     config: &CliConfig,
     api_url: &url::Url,
+    api_version: &str,
+    timeout: std::time::Duration,
 ) -> Result<GeneratedClient, ClientBuildError> {
-    let auth = config.auth.as_ref().ok_or(ClientBuildError::NoAuthToken)?;
-    let mut base = api_url.clone();
-    base.set_path("/api/v1");
-    let base_url = base.to_string();
+    let auth = config.auth().ok_or(ClientBuildError::NoAuthToken)?;
+    // Fail before making a request that can only be rejected with a 401
+    if auth.is_expired() {
+        return Err(ClientBuildError::TokenExpired);
+    }
+    let base_url = api_base_url(&config.platform_url(api_url), api_version).to_string();
 
     let mut headers = reqwest::header::HeaderMap::new();
     let auth_value = format!("Bearer {}", auth.access_token);
@@ -27,6 +148,7 @@ pub fn authenticated_client(
 
     let http_client = reqwest::Client::builder()
         .default_headers(headers)
+        .timeout(timeout)
         .build()
         .map_err(|e| {
             ClientBuildError::InvalidConfig(format!("Failed to build HTTP client: {e}"))
@@ -34,3 +156,80 @@ pub fn authenticated_client(
 
     Ok(GeneratedClient::new_with_client(&base_url, http_client))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UserAuth;
+    use chrono::{
+        Duration,
+        Utc,
+    };
+
+    #[test]
+    fn test_api_base_url() {
+        let platform: url::Url = "https://app.phylax.systems".parse().unwrap();
+        assert_eq!(
+            api_base_url(&platform, "v1").as_str(),
+            "https://app.phylax.systems/api/v1"
+        );
+        assert_eq!(
+            api_base_url(&platform, "v2").as_str(),
+            "https://app.phylax.systems/api/v2"
+        );
+    }
+
+    #[test]
+    fn test_authenticated_client_rejects_expired_token() {
+        let api_url: url::Url = "https://app.phylax.systems".parse().unwrap();
+        let mut config = CliConfig {
+            auth: Some(UserAuth {
+                access_token: "token".to_string(),
+                expires_at: Utc::now() - Duration::minutes(1),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(matches!(
+            authenticated_client(&config, &api_url, "v1", std::time::Duration::from_secs(30)),
+            Err(ClientBuildError::TokenExpired)
+        ));
+
+        if let Some(auth) = config.auth.as_mut() {
+            auth.expires_at = Utc::now() + Duration::hours(1);
+        }
+        assert!(
+            authenticated_client(&config, &api_url, "v1", std::time::Duration::from_secs(30))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), DEFAULT_RETRY_AFTER);
+
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), std::time::Duration::from_secs(7));
+
+        headers.insert(RETRY_AFTER, "86400".parse().unwrap());
+        assert_eq!(retry_after(&headers), MAX_RETRY_AFTER);
+
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), std::time::Duration::ZERO);
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), DEFAULT_RETRY_AFTER);
+    }
+
+    #[test]
+    fn test_parse_api_version() {
+        assert_eq!(parse_api_version("v2").unwrap(), "v2");
+        assert!(parse_api_version("2").is_err());
+        assert!(parse_api_version("v").is_err());
+        assert!(parse_api_version("v1/../x").is_err());
+    }
+}
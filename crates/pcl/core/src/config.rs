@@ -1,4 +1,7 @@
-use crate::error::ConfigError;
+use crate::{
+    DEFAULT_PLATFORM_URL,
+    error::ConfigError,
+};
 use alloy_primitives::Address;
 use chrono::{
     DateTime,
@@ -14,12 +17,22 @@ use serde::{
 };
 
 use std::{
+    borrow::Cow,
     fmt,
+    io::{
+        IsTerminal,
+        Write,
+    },
     path::{
         Path,
         PathBuf,
     },
+    time::{
+        Duration,
+        Instant,
+    },
 };
+use url::Url;
 use uuid::Uuid;
 
 /// Legacy directory name for storing PCL configuration (deprecated)
@@ -28,15 +41,75 @@ const LEGACY_CONFIG_DIR: &str = ".pcl";
 const CONFIG_DIR_NAME: &str = "pcl";
 /// Configuration file name
 pub const CONFIG_FILE: &str = "config.toml";
+/// Project-local configuration file name, discovered from the current directory upwards
+pub const PROJECT_CONFIG_FILE: &str = ".pcl.toml";
+/// Version of the config file format written by this build; see [`CliConfig::migrate`]
+pub const CONFIG_VERSION: u32 = 1;
+/// Advisory lock file held in the config directory while the config is written
+const LOCK_FILE: &str = "config.toml.lock";
+/// How long to wait for another pcl process to finish writing the config
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+/// Locks older than this were left behind by a crashed process and are removed
+const STALE_LOCK_AGE: Duration = Duration::from_secs(60);
+/// Environment variable with an access token, used when no login is stored
+pub const ACCESS_TOKEN_ENV: &str = "PCL_ACCESS_TOKEN";
 
 /// Main configuration structure for PCL
 ///
 /// This struct holds all the configuration data for the PCL tool,
 /// including authentication details.
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CliConfig {
+    /// Config file format version, upgraded on read by [`CliConfig::migrate`]
+    #[serde(default)]
+    pub version: u32,
+    /// Platform base URL used by commands whose URL flag is left at the default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_url: Option<String>,
+    /// Project of the last successful `pcl apply`, preselected next time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_project: Option<Uuid>,
+    /// Assertion file extensions, most preferred first; defaults to `.a.sol` and `.sol`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assertion_extensions: Option<Vec<String>>,
     /// Optional authentication details
     pub auth: Option<UserAuth>,
+    /// Project-local overrides from `.pcl.toml`, never written back to the global config
+    #[serde(skip)]
+    pub project: Option<ProjectConfig>,
+}
+
+impl Default for CliConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            platform_url: None,
+            last_project: None,
+            assertion_extensions: None,
+            auth: None,
+            project: None,
+        }
+    }
+}
+
+/// Project-local configuration read from a `.pcl.toml` checked into a repository
+///
+/// Only non-secret settings are honoured; credentials always come from the
+/// global config.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ProjectConfig {
+    /// File the configuration was read from
+    #[serde(skip)]
+    pub path: PathBuf,
+    /// Platform base URL, taking precedence over the global `platform_url`
+    #[serde(default)]
+    pub platform_url: Option<String>,
+    /// Assertion file extensions, taking precedence over the global `assertion_extensions`
+    #[serde(default)]
+    pub assertion_extensions: Option<Vec<String>>,
+    /// Credentials found in the project file; ignored with a warning
+    #[serde(default)]
+    auth: Option<toml::Value>,
 }
 
 /// Command-line arguments for configuration management
@@ -50,34 +123,290 @@ pub struct ConfigArgs {
 #[derive(clap::Subcommand)]
 enum ConfigCommand {
     #[command(about = "Display the current configuration")]
-    Show,
-    #[command(about = "Delete the current configuration")]
-    Delete,
+    Show {
+        /// Print a redacted JSON view of the configuration
+        #[arg(long)]
+        json: bool,
+    },
+    #[command(
+        about = "Delete the current configuration",
+        after_help = "Example: pcl config delete --auth-only --yes"
+    )]
+    Delete {
+        /// Delete without asking for confirmation
+        #[arg(long, short = 'y')]
+        yes: bool,
+        /// Only remove stored credentials and keep other settings
+        #[arg(long)]
+        auth_only: bool,
+    },
+    #[command(
+        about = "Export the current configuration, including credentials",
+        after_help = "Example: pcl config export ~/pcl-config.toml"
+    )]
+    Export {
+        /// File to write the configuration to. Prints to stdout when omitted.
+        path: Option<PathBuf>,
+        /// Overwrite the file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+    #[command(
+        about = "Import a configuration previously written by `pcl config export`",
+        after_help = "Example: pcl config import ~/pcl-config.toml --merge"
+    )]
+    Import {
+        /// File to read the configuration from
+        path: PathBuf,
+        /// Merge into the current configuration instead of replacing it.
+        /// Imported entries win on collision.
+        #[arg(long)]
+        merge: bool,
+    },
+    #[command(
+        about = "Check the config file for problems without running a command",
+        after_help = "Example: pcl config validate --json"
+    )]
+    Validate {
+        /// Print the problems found as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    #[command(
+        about = "Set the platform URL used by auth, apply and download",
+        after_help = "Example: pcl config set-platform-url https://app.phylax.systems"
+    )]
+    SetPlatformUrl {
+        /// Base URL of the Credible Layer platform
+        url: Url,
+    },
 }
 
 impl ConfigArgs {
     /// Executes the configuration command
     ///
     /// # Arguments
+    /// * `cli_args` - Global CLI arguments
     /// * `config` - The configuration to operate on
     ///
     /// # Returns
     /// * `Result<(), ConfigError>` - Success or error
-    pub fn run(&self, config: &mut CliConfig) -> Result<(), ConfigError> {
-        match self.command {
-            ConfigCommand::Show => {
-                println!("{config}");
+    pub fn run(&self, cli_args: &CliArgs, config: &mut CliConfig) -> Result<(), ConfigError> {
+        match &self.command {
+            ConfigCommand::Show { json } => {
+                if *json || cli_args.json_output() {
+                    println!("{}", serde_json::to_string_pretty(&config.redacted())?);
+                } else {
+                    println!("{config}");
+                }
+                Ok(())
+            }
+            ConfigCommand::Delete { yes, auth_only } => {
+                let target = if *auth_only {
+                    "stored credentials"
+                } else {
+                    "the entire configuration"
+                };
+                if !*yes && !confirm_delete(target)? {
+                    return Err(ConfigError::DeleteCancelled);
+                }
+                if *auth_only {
+                    config.auth = None;
+                } else {
+                    *config = CliConfig::default();
+                }
+                eprintln!("Deleted {target}");
+                Ok(())
+            }
+            ConfigCommand::Export { path, force } => {
+                let config_str = config.to_toml()?;
+                match path {
+                    Some(path) => {
+                        write_private(path, &config_str, *force)?;
+                        eprintln!("Configuration exported to {}", path.display());
+                    }
+                    None => print!("{config_str}"),
+                }
+                Ok(())
+            }
+            ConfigCommand::Import { path, merge } => {
+                let imported = CliConfig::read_from_path(path)?;
+                if *merge {
+                    config.merge(imported);
+                } else {
+                    *config = imported;
+                }
+                eprintln!("Configuration imported from {}", path.display());
                 Ok(())
             }
-            ConfigCommand::Delete => {
-                *config = CliConfig::default();
+            ConfigCommand::SetPlatformUrl { url } => {
+                config.platform_url = Some(url.to_string());
+                eprintln!("Platform URL set to {url}");
                 Ok(())
             }
+            ConfigCommand::Validate { json } => {
+                let config_file = CliConfig::resolve_config_dir(cli_args)?.join(CONFIG_FILE);
+                let mut problems = CliConfig::validate_file(&config_file);
+                if let Ok(current_dir) = std::env::current_dir() {
+                    problems.extend(ProjectConfig::validate(&current_dir));
+                }
+
+                if *json || cli_args.json_output() {
+                    println!(
+                        "{}",
+                        serde_json::to_string_pretty(&serde_json::json!({
+                            "status": if problems.is_empty() { "ok" } else { "invalid" },
+                            "path": config_file,
+                            "problems": problems,
+                        }))?
+                    );
+                } else if problems.is_empty() {
+                    println!("✅ {} is valid", config_file.display());
+                } else {
+                    for problem in &problems {
+                        println!("❌ {problem}");
+                    }
+                }
+
+                if problems.is_empty() {
+                    Ok(())
+                } else {
+                    Err(ConfigError::ValidationFailed(problems.len()))
+                }
+            }
         }
     }
 }
 
+/// Asks the user to confirm a delete, refusing when there is no terminal to ask on
+fn confirm_delete(target: &str) -> Result<bool, ConfigError> {
+    if !std::io::stdin().is_terminal() {
+        return Err(ConfigError::ConfirmationRequired);
+    }
+    inquire::Confirm::new(&format!("Delete {target}?"))
+        .with_default(false)
+        .prompt()
+        .map_err(ConfigError::PromptFailed)
+}
+
+/// Redacted view of [`CliConfig`] printed by `pcl config show --json`
+#[derive(Debug, Serialize)]
+pub struct RedactedConfig {
+    pub config_path: Option<PathBuf>,
+    pub cache_path: Option<PathBuf>,
+    pub platform_url: Option<String>,
+    pub project_config_path: Option<PathBuf>,
+    pub project_platform_url: Option<String>,
+    pub last_project: Option<Uuid>,
+    pub auth: Option<RedactedAuth>,
+}
+
+/// Redacted view of [`UserAuth`]; tokens are reported as `[Set]` or `[Unset]`
+#[derive(Debug, Serialize)]
+pub struct RedactedAuth {
+    pub user: String,
+    pub user_id: Option<Uuid>,
+    pub wallet_address: Option<Address>,
+    pub email: Option<String>,
+    pub issued_by: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub expired: bool,
+    pub access_token: &'static str,
+    pub refresh_token: &'static str,
+}
+
 impl CliConfig {
+    /// Returns a view of the configuration that is safe to share
+    pub fn redacted(&self) -> RedactedConfig {
+        let redact = |token: &str| if token.is_empty() { "[Unset]" } else { "[Set]" };
+        RedactedConfig {
+            config_path: Self::get_config_dir().ok().map(|dir| dir.join(CONFIG_FILE)),
+            cache_path: pcl_phoundry::flatten_cache::cache_dir(),
+            platform_url: self.platform_url.clone(),
+            project_config_path: self.project.as_ref().map(|project| project.path.clone()),
+            project_platform_url: self
+                .project
+                .as_ref()
+                .and_then(|project| project.platform_url.clone()),
+            last_project: self.last_project,
+            auth: self.auth.as_ref().map(|auth| {
+                RedactedAuth {
+                    user: auth.display_name(),
+                    user_id: auth.user_id,
+                    wallet_address: auth.wallet_address,
+                    email: auth.email.clone(),
+                    issued_by: auth.platform_url.clone(),
+                    expires_at: auth.expires_at,
+                    expired: auth.is_expired(),
+                    access_token: redact(&auth.access_token),
+                    refresh_token: redact(&auth.refresh_token),
+                }
+            }),
+        }
+    }
+
+    /// Resolves the platform URL a command should talk to
+    ///
+    /// A URL passed explicitly through a flag or environment variable wins.
+    /// When the command's URL is still the built-in default, the URL from a
+    /// project-local `.pcl.toml` is used, then the URL set with
+    /// `pcl config set-platform-url`.
+    ///
+    /// # Arguments
+    /// * `command_url` - URL the command was invoked with
+    ///
+    /// # Returns
+    /// * `Url` - URL to use for requests
+    pub fn platform_url(&self, command_url: &Url) -> Url {
+        let is_default = command_url.as_str().trim_end_matches('/') == DEFAULT_PLATFORM_URL;
+        let configured = self
+            .project
+            .as_ref()
+            .and_then(|project| project.platform_url.as_deref())
+            .or(self.platform_url.as_deref());
+        match configured.map(Url::parse) {
+            Some(Ok(configured)) if is_default => configured,
+            _ => command_url.clone(),
+        }
+    }
+
+    /// Assertion file extensions to try, from `.pcl.toml` or the global config
+    ///
+    /// # Returns
+    /// * `&[String]` - Extensions, empty when neither file sets any so the
+    ///   built-in `.a.sol`/`.sol` defaults apply
+    pub fn assertion_extensions(&self) -> &[String] {
+        self.project
+            .as_ref()
+            .and_then(|project| project.assertion_extensions.as_deref())
+            .or(self.assertion_extensions.as_deref())
+            .unwrap_or_default()
+    }
+
+    /// Returns the authentication to use for platform requests
+    ///
+    /// Precedence: stored login, then the `PCL_ACCESS_TOKEN` environment
+    /// variable, then unauthenticated. A token from the environment is never
+    /// written to the config file.
+    pub fn auth(&self) -> Option<Cow<'_, UserAuth>> {
+        self.auth_or_token(std::env::var(ACCESS_TOKEN_ENV).ok())
+    }
+
+    /// Returns the stored authentication, or a minimal one built from `token`
+    fn auth_or_token(&self, token: Option<String>) -> Option<Cow<'_, UserAuth>> {
+        if let Some(auth) = &self.auth {
+            return Some(Cow::Borrowed(auth));
+        }
+        token.filter(|token| !token.is_empty()).map(|access_token| {
+            // The token's lifetime is unknown locally; the platform rejects it once expired
+            Cow::Owned(UserAuth {
+                access_token,
+                expires_at: DateTime::<Utc>::MAX_UTC,
+                ..Default::default()
+            })
+        })
+    }
+
     /// Writes the configuration to the default config file, or a specific directory
     ///
     /// # Arguments
@@ -86,12 +415,7 @@ impl CliConfig {
     /// # Returns
     /// * `Result<(), ConfigError>` - Success or error
     pub fn write_to_file(&self, cli_args: &CliArgs) -> Result<(), ConfigError> {
-        self.write_to_file_at_dir(
-            &cli_args
-                .config_dir
-                .clone()
-                .unwrap_or(Self::get_config_dir()),
-        )
+        self.write_to_file_at_dir(&Self::resolve_config_dir(cli_args)?)
     }
 
     /// Writes the configuration to a specific directory
@@ -110,9 +434,249 @@ impl CliConfig {
         Self::ensure_writable_file(&config_file)?;
 
         // Serialize and write config
-        let config_str = toml::to_string(self).map_err(ConfigError::SerializeError)?;
-        std::fs::write(config_file, config_str).map_err(ConfigError::WriteError)?;
-        Ok(())
+        let config_str = self.to_toml()?;
+        let _lock = ConfigLock::acquire(config_dir)?;
+        Self::write_atomically(&config_file, &config_str)
+    }
+
+    /// Writes the changes made since `original` was read, keeping everything else on disk
+    ///
+    /// The file is re-read under the config lock, so concurrent pcl processes
+    /// only overwrite the settings they changed themselves. Nothing is written
+    /// when the configuration is unchanged.
+    ///
+    /// # Arguments
+    /// * `original` - Configuration as it was read at startup
+    /// * `cli_args` - Command line arguments
+    ///
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Success or error
+    pub fn write_changes(&self, original: &Self, cli_args: &CliArgs) -> Result<(), ConfigError> {
+        self.write_changes_at_dir(original, &Self::resolve_config_dir(cli_args)?)
+    }
+
+    /// Writes the changes made since `original` was read to a specific directory
+    ///
+    /// # Arguments
+    /// * `original` - Configuration as it was read at startup
+    /// * `config_dir` - Directory to write the config file to
+    ///
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Success or error
+    fn write_changes_at_dir(
+        &self,
+        original: &Self,
+        config_dir: &PathBuf,
+    ) -> Result<(), ConfigError> {
+        if self.platform_url == original.platform_url
+            && self.last_project == original.last_project
+            && self.assertion_extensions == original.assertion_extensions
+            && self.auth == original.auth
+        {
+            return Ok(());
+        }
+
+        Self::ensure_writable_directory(config_dir)?;
+        let config_file = config_dir.join(CONFIG_FILE);
+        Self::ensure_writable_file(&config_file)?;
+
+        let _lock = ConfigLock::acquire(config_dir)?;
+        // A file that fails to parse (e.g. from a newer pcl) is left untouched
+        let mut current = if config_file.exists() {
+            Self::read_from_path(&config_file)?
+        } else {
+            Self::default()
+        };
+        if self.platform_url != original.platform_url {
+            current.platform_url.clone_from(&self.platform_url);
+        }
+        if self.last_project != original.last_project {
+            current.last_project = self.last_project;
+        }
+        if self.assertion_extensions != original.assertion_extensions {
+            current
+                .assertion_extensions
+                .clone_from(&self.assertion_extensions);
+        }
+        if self.auth != original.auth {
+            current.auth.clone_from(&self.auth);
+        }
+        current.version = CONFIG_VERSION;
+        Self::write_atomically(&config_file, &current.to_toml()?)
+    }
+
+    /// Replaces `path` through a temporary file so readers never see a partial config
+    ///
+    /// # Arguments
+    /// * `path` - Config file to replace
+    /// * `contents` - New file contents
+    ///
+    /// # Returns
+    /// * `Result<(), ConfigError>` - Success or error
+    fn write_atomically(path: &Path, contents: &str) -> Result<(), ConfigError> {
+        let temp_file = path.with_extension(format!("toml.{}.tmp", std::process::id()));
+        // The config holds the auth token, so keep it owner-only like an export
+        write_private(&temp_file, contents, true)?;
+        std::fs::rename(&temp_file, path).map_err(|e| {
+            std::fs::remove_file(&temp_file).ok();
+            ConfigError::WriteError(e)
+        })
+    }
+
+    /// Serializes the configuration to a TOML string
+    ///
+    /// # Returns
+    /// * `Result<String, ConfigError>` - Serialized config or error
+    fn to_toml(&self) -> Result<String, ConfigError> {
+        toml::to_string(self).map_err(ConfigError::SerializeError)
+    }
+
+    /// Reads and parses a configuration file at an exact path
+    ///
+    /// # Arguments
+    /// * `path` - Path to the config file
+    ///
+    /// # Returns
+    /// * `Result<Self, ConfigError>` - Configuration or error
+    fn read_from_path(path: &Path) -> Result<Self, ConfigError> {
+        Self::read_versioned(path).map(|(config, _)| config)
+    }
+
+    /// Reads a configuration file, upgrading it to [`CONFIG_VERSION`]
+    ///
+    /// # Arguments
+    /// * `path` - Path to the config file
+    ///
+    /// # Returns
+    /// * `Result<(Self, u32), ConfigError>` - Configuration and the version it was written with
+    fn read_versioned(path: &Path) -> Result<(Self, u32), ConfigError> {
+        let config_str = std::fs::read_to_string(path).map_err(ConfigError::ReadError)?;
+        let mut table: toml::Table =
+            toml::from_str(&config_str).map_err(ConfigError::ParseError)?;
+        let file_version = Self::migrate(&mut table)?;
+        let config = toml::Value::Table(table)
+            .try_into()
+            .map_err(ConfigError::ParseError)?;
+        Ok((config, file_version))
+    }
+
+    /// Upgrades a parsed config file to [`CONFIG_VERSION`] in place
+    ///
+    /// Files without a `version` key predate versioning and are version 0.
+    /// Each format change adds a step here so older files keep loading.
+    ///
+    /// # Arguments
+    /// * `table` - Parsed config file
+    ///
+    /// # Returns
+    /// * `Result<u32, ConfigError>` - Version the file was written with
+    fn migrate(table: &mut toml::Table) -> Result<u32, ConfigError> {
+        let file_version = match table.get("version") {
+            None => 0,
+            Some(toml::Value::Integer(version)) => {
+                u32::try_from(*version).map_err(|_| ConfigError::UnsupportedVersion(*version))?
+            }
+            Some(other) => {
+                return Err(ConfigError::InvalidVersion(other.to_string()));
+            }
+        };
+        if file_version > CONFIG_VERSION {
+            return Err(ConfigError::UnsupportedVersion(i64::from(file_version)));
+        }
+
+        // 0 -> 1: the layout is unchanged; files only gain the version marker
+
+        table.insert(
+            "version".to_string(),
+            toml::Value::Integer(i64::from(CONFIG_VERSION)),
+        );
+        Ok(file_version)
+    }
+
+    /// Lists the problems in a config file, without falling back to defaults
+    ///
+    /// A missing file is valid: commands then start from an empty configuration.
+    ///
+    /// # Arguments
+    /// * `path` - Config file to check
+    ///
+    /// # Returns
+    /// * `Vec<String>` - One message per problem, empty when the file is valid
+    fn validate_file(path: &Path) -> Vec<String> {
+        if !path.exists() {
+            return Vec::new();
+        }
+        let config = match Self::read_from_path(path) {
+            Ok(config) => config,
+            Err(e) => return vec![format!("{}: {e}", path.display())],
+        };
+
+        let mut problems = Vec::new();
+        if let Some(platform_url) = &config.platform_url
+            && let Err(e) = Url::parse(platform_url)
+        {
+            problems.push(format!(
+                "platform_url {platform_url:?} is not a valid URL: {e}"
+            ));
+        }
+        if let Some(auth) = &config.auth {
+            if auth.access_token.is_empty() {
+                problems.push("auth.access_token is empty; run `pcl auth login`".to_string());
+            }
+            if auth.is_expired() {
+                problems.push(format!(
+                    "token for {} expired at {}; run `pcl auth login`",
+                    auth.display_name(),
+                    auth.expires_at.format("%Y-%m-%d %H:%M:%S UTC")
+                ));
+            }
+            if let Some(platform_url) = &auth.platform_url
+                && let Err(e) = Url::parse(platform_url)
+            {
+                problems.push(format!(
+                    "auth.platform_url {platform_url:?} is not a valid URL: {e}"
+                ));
+            }
+        }
+        problems
+    }
+
+    /// Merges another configuration into this one
+    ///
+    /// Entries from `other` take precedence; a warning is printed for every
+    /// existing entry that gets overwritten.
+    ///
+    /// # Arguments
+    /// * `other` - Configuration to merge in
+    fn merge(&mut self, other: Self) {
+        if let Some(imported_url) = other.platform_url {
+            if let Some(existing_url) = &self.platform_url
+                && *existing_url != imported_url
+            {
+                eprintln!(
+                    "{}: Overwriting platform URL {existing_url} with imported {imported_url}",
+                    "Warning".yellow().bold(),
+                );
+            }
+            self.platform_url = Some(imported_url);
+        }
+        if other.last_project.is_some() {
+            self.last_project = other.last_project;
+        }
+        if other.assertion_extensions.is_some() {
+            self.assertion_extensions = other.assertion_extensions;
+        }
+        if let Some(imported_auth) = other.auth {
+            if let Some(existing_auth) = &self.auth {
+                eprintln!(
+                    "{}: Overwriting authentication for {} with imported authentication for {}",
+                    "Warning".yellow().bold(),
+                    existing_auth.display_name(),
+                    imported_auth.display_name()
+                );
+            }
+            self.auth = Some(imported_auth);
+        }
     }
 
     /// Ensures a directory exists and is writable
@@ -122,7 +686,7 @@ impl CliConfig {
     ///
     /// # Returns
     /// * `Result<(), ConfigError>` - Success or error
-    fn ensure_writable_directory(dir: &PathBuf) -> Result<(), ConfigError> {
+    pub(crate) fn ensure_writable_directory(dir: &PathBuf) -> Result<(), ConfigError> {
         if !dir.exists() {
             std::fs::create_dir_all(dir).map_err(|e| {
                 ConfigError::WriteError(std::io::Error::new(
@@ -174,13 +738,12 @@ impl CliConfig {
     /// Gets the legacy configuration directory path (~/.pcl)
     ///
     /// # Returns
-    /// * `PathBuf` - Path to the legacy config directory
-    ///
-    /// # Panics
-    ///
-    /// Will panic if it does not find the home directory
-    fn get_legacy_config_dir() -> PathBuf {
-        home_dir().unwrap().join(LEGACY_CONFIG_DIR)
+    /// * `Result<PathBuf, ConfigError>` - Path to the legacy config directory, or
+    ///   `ConfigError::NoHomeDirectory` if the home directory cannot be resolved
+    fn get_legacy_config_dir() -> Result<PathBuf, ConfigError> {
+        home_dir()
+            .map(|home| home.join(LEGACY_CONFIG_DIR))
+            .ok_or(ConfigError::NoHomeDirectory)
     }
 
     /// Gets the default configuration directory path
@@ -190,15 +753,34 @@ impl CliConfig {
     /// - `~/.config/pcl` otherwise
     ///
     /// # Returns
-    /// * `PathBuf` - Path to the config directory
+    /// * `Result<PathBuf, ConfigError>` - Path to the config directory, or
+    ///   `ConfigError::NoHomeDirectory` if neither `XDG_CONFIG_HOME` nor the
+    ///   home directory can be resolved
+    pub fn get_config_dir() -> Result<PathBuf, ConfigError> {
+        if let Some(xdg_config_home) = std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty())
+        {
+            return Ok(PathBuf::from(xdg_config_home).join(CONFIG_DIR_NAME));
+        }
+        home_dir()
+            .map(|home| home.join(".config").join(CONFIG_DIR_NAME))
+            .ok_or(ConfigError::NoHomeDirectory)
+    }
+
+    /// Resolves the configuration directory for the current invocation
+    ///
+    /// An explicit `config_dir` CLI argument takes precedence over the default
+    /// location, so the home directory is only consulted when no override is set.
     ///
-    /// # Panics
+    /// # Arguments
+    /// * `cli_args` - Command line arguments
     ///
-    /// Will panic if it does not find the home directory
-    pub fn get_config_dir() -> PathBuf {
-        std::env::var("XDG_CONFIG_HOME")
-            .map_or_else(|_| home_dir().unwrap().join(".config"), PathBuf::from)
-            .join(CONFIG_DIR_NAME)
+    /// # Returns
+    /// * `Result<PathBuf, ConfigError>` - Path to the config directory
+    pub fn resolve_config_dir(cli_args: &CliArgs) -> Result<PathBuf, ConfigError> {
+        match &cli_args.config_dir {
+            Some(config_dir) => Ok(config_dir.clone()),
+            None => Self::get_config_dir(),
+        }
     }
 
     /// Migrates configuration from the legacy location (`~/.pcl`) to the new
@@ -213,8 +795,8 @@ impl CliConfig {
     /// * `Ok(false)` - No migration needed
     /// * `Err(ConfigError)` - Migration failed
     pub fn migrate_legacy_config() -> Result<bool, ConfigError> {
-        let legacy_dir = Self::get_legacy_config_dir();
-        let new_dir = Self::get_config_dir();
+        let legacy_dir = Self::get_legacy_config_dir()?;
+        let new_dir = Self::get_config_dir()?;
 
         // Only migrate if legacy exists and new doesn't
         if legacy_dir.exists() && !new_dir.exists() {
@@ -268,8 +850,14 @@ impl CliConfig {
             })?;
         }
 
-        let config_str = std::fs::read_to_string(config_file).map_err(ConfigError::ReadError)?;
-        toml::from_str(&config_str).map_err(ConfigError::ParseError)
+        let (config, file_version) = Self::read_versioned(&config_file)?;
+        if file_version < CONFIG_VERSION {
+            // Persist the upgrade; a read-only config still loads fine
+            if let Err(e) = config.write_to_file_at_dir(&config_dir.to_path_buf()) {
+                tracing::debug!(error = %e, "failed to write migrated config");
+            }
+        }
+        Ok(config)
     }
 
     /// Reads configuration from the default config file, or a specific directory
@@ -278,6 +866,13 @@ impl CliConfig {
     /// any existing configuration from the legacy location (~/.pcl) to the new
     /// XDG-compliant location.
     ///
+    /// A `.pcl.toml` found in the current directory or one of its parents is
+    /// layered on top of the global config. Precedence for URLs, highest first:
+    /// explicit flag or environment variable, `.pcl.toml`, global config,
+    /// built-in default. Credentials are only ever read from the global config.
+    /// A `.pcl.toml` that cannot be read is ignored with a warning, so it never
+    /// costs the global config.
+    ///
     /// # Arguments
     /// * `cli_args` - Command line arguments
     ///
@@ -290,22 +885,209 @@ impl CliConfig {
             let _ = Self::migrate_legacy_config();
         }
 
-        Self::read_from_file_at_dir(
-            &cli_args
-                .config_dir
-                .clone()
-                .unwrap_or(Self::get_config_dir()),
-        )
+        let mut config = Self::read_from_file_at_dir(&Self::resolve_config_dir(cli_args)?)?;
+        if let Ok(current_dir) = std::env::current_dir() {
+            config.project = Self::discover_project(&current_dir);
+        }
+        Ok(config)
+    }
+
+    /// Finds the nearest `.pcl.toml`, warning about and skipping a broken one
+    ///
+    /// # Arguments
+    /// * `start` - Directory to start searching from
+    ///
+    /// # Returns
+    /// * `Option<ProjectConfig>` - Project config, `None` if absent or unreadable
+    fn discover_project(start: &Path) -> Option<ProjectConfig> {
+        ProjectConfig::discover(start).unwrap_or_else(|e| {
+            eprintln!(
+                "{}: Ignoring {PROJECT_CONFIG_FILE}: {e}",
+                "Warning".yellow().bold(),
+            );
+            None
+        })
+    }
+}
+
+/// Writes an export, readable only by the owner since it holds credentials
+///
+/// # Arguments
+/// * `path` - File to write
+/// * `contents` - File contents
+/// * `overwrite` - Replace the file if it already exists
+///
+/// # Returns
+/// * `Result<(), ConfigError>` - Success or error
+fn write_private(path: &Path, contents: &str, overwrite: bool) -> Result<(), ConfigError> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::AlreadyExists {
+            ConfigError::ExportExists(path.to_path_buf())
+        } else {
+            ConfigError::WriteError(e)
+        }
+    })?;
+    // The mode only applies to new files, so tighten an overwritten one too
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(ConfigError::WriteError)?;
+    }
+    file.write_all(contents.as_bytes())
+        .map_err(ConfigError::WriteError)
+}
+
+/// Advisory lock on the config directory, released on drop
+struct ConfigLock {
+    path: PathBuf,
+}
+
+impl ConfigLock {
+    /// Waits until no other pcl process holds the lock, then takes it
+    ///
+    /// # Arguments
+    /// * `config_dir` - Directory holding the config file
+    ///
+    /// # Returns
+    /// * `Result<Self, ConfigError>` - Held lock or error
+    fn acquire(config_dir: &Path) -> Result<Self, ConfigError> {
+        let path = config_dir.join(LOCK_FILE);
+        let started = Instant::now();
+        loop {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Self { path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if Self::is_stale(&path) {
+                        std::fs::remove_file(&path).ok();
+                        continue;
+                    }
+                    if started.elapsed() > LOCK_TIMEOUT {
+                        return Err(ConfigError::LockTimeout(path));
+                    }
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => return Err(ConfigError::WriteError(e)),
+            }
+        }
+    }
+
+    fn is_stale(path: &Path) -> bool {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age > STALE_LOCK_AGE)
+    }
+}
+
+impl Drop for ConfigLock {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.path).ok();
+    }
+}
+
+impl ProjectConfig {
+    /// Finds and reads the nearest `.pcl.toml` in `start` or one of its parents
+    ///
+    /// # Arguments
+    /// * `start` - Directory to start searching from
+    ///
+    /// # Returns
+    /// * `Result<Option<Self>, ConfigError>` - Project config, `None` if no file was found
+    pub fn discover(start: &Path) -> Result<Option<Self>, ConfigError> {
+        let Some(path) = start
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+            .find(|path| path.is_file())
+        else {
+            return Ok(None);
+        };
+
+        let config_str = std::fs::read_to_string(&path).map_err(ConfigError::ReadError)?;
+        let mut project: Self = toml::from_str(&config_str).map_err(ConfigError::ParseError)?;
+        if project.auth.take().is_some() {
+            eprintln!(
+                "{}: Ignoring credentials in {}; authentication is only read from the global config",
+                "Warning".yellow().bold(),
+                path.display()
+            );
+        }
+        project.path = path;
+        Ok(Some(project))
+    }
+
+    /// Lists the problems in the nearest `.pcl.toml`, if there is one
+    ///
+    /// # Arguments
+    /// * `start` - Directory to start searching from
+    ///
+    /// # Returns
+    /// * `Vec<String>` - One message per problem, empty when the file is valid or absent
+    fn validate(start: &Path) -> Vec<String> {
+        let project = match Self::discover(start) {
+            Ok(Some(project)) => project,
+            Ok(None) => return Vec::new(),
+            Err(e) => return vec![format!("{PROJECT_CONFIG_FILE}: {e}")],
+        };
+        match &project.platform_url {
+            Some(platform_url) => {
+                Url::parse(platform_url)
+                    .err()
+                    .map(|e| {
+                        format!(
+                            "{}: platform_url {platform_url:?} is not a valid URL: {e}",
+                            project.path.display()
+                        )
+                    })
+                    .into_iter()
+                    .collect()
+            }
+            None => Vec::new(),
+        }
     }
 }
 
 impl fmt::Display for CliConfig {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let config_path = Self::get_config_dir().join(CONFIG_FILE);
-
         writeln!(f, "PCL Configuration")?;
         writeln!(f, "==================")?;
-        writeln!(f, "Config path: {}", config_path.display())?;
+        match Self::get_config_dir() {
+            Ok(config_dir) => {
+                writeln!(f, "Config path: {}", config_dir.join(CONFIG_FILE).display())?;
+            }
+            Err(e) => writeln!(f, "Config path: unavailable ({e})")?,
+        }
+        match pcl_phoundry::flatten_cache::cache_dir() {
+            Some(cache_dir) => writeln!(f, "Cache path: {}", cache_dir.display())?,
+            None => writeln!(f, "Cache path: unavailable")?,
+        }
+
+        if let Some(platform_url) = &self.platform_url {
+            writeln!(f, "Platform URL: {platform_url}")?;
+        }
+        if let Some(project) = &self.project {
+            writeln!(f, "Project config: {}", project.path.display())?;
+            if let Some(platform_url) = &project.platform_url {
+                writeln!(f, "Project platform URL: {platform_url}")?;
+            }
+        }
+        if let Some(last_project) = &self.last_project {
+            writeln!(f, "Last project: {last_project}")?;
+        }
 
         match &self.auth {
             Some(auth) => writeln!(f, "{auth}")?,
@@ -317,7 +1099,7 @@ impl fmt::Display for CliConfig {
 }
 
 /// Authentication details for a user
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct UserAuth {
     /// Access token for API authentication
     pub access_token: String,
@@ -335,9 +1117,26 @@ pub struct UserAuth {
     /// Email address of the user (for email-based auth)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub email: Option<String>,
+    /// Platform URL the token was issued by; unset for logins stored by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform_url: Option<String>,
 }
 
 impl UserAuth {
+    /// Returns the issuing platform URL when it differs from `platform_url`
+    ///
+    /// Tokens are only accepted by the platform that issued them, so a mismatch
+    /// means requests will be rejected with a 401.
+    pub fn issuer_mismatch(&self, platform_url: &Url) -> Option<Url> {
+        let issuer = Url::parse(self.platform_url.as_deref()?).ok()?;
+        (issuer.origin() != platform_url.origin()).then_some(issuer)
+    }
+
+    /// Returns true once the access token's expiry time has passed.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at < Utc::now()
+    }
+
     /// Returns the best available display name for this user.
     pub fn display_name(&self) -> String {
         if let Some(addr) = &self.wallet_address
@@ -359,6 +1158,9 @@ impl fmt::Display for UserAuth {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "Authentication:")?;
         writeln!(f, "  User: {}", self.display_name())?;
+        if let Some(platform_url) = &self.platform_url {
+            writeln!(f, "  Issued by: {platform_url}")?;
+        }
         let now = Utc::now();
         let expired = self.expires_at < now;
         let expiry_text = self.expires_at.format("%Y-%m-%d %H:%M:%S UTC").to_string();
@@ -415,6 +1217,136 @@ mod tests {
         fs::set_permissions(path, perms)
     }
 
+    #[test]
+    fn test_write_changes_keeps_concurrent_updates() {
+        let (config_dir, _temp_dir) = setup_config_dir();
+        let original = CliConfig {
+            platform_url: Some("https://old.example".to_string()),
+            ..Default::default()
+        };
+        original.write_to_file_at_dir(&config_dir).unwrap();
+
+        // Another process logs in while this one is running
+        let other = CliConfig {
+            auth: Some(UserAuth {
+                access_token: "other_token".to_string(),
+                ..Default::default()
+            }),
+            ..original.clone()
+        };
+        other.write_to_file_at_dir(&config_dir).unwrap();
+
+        let mut config = original.clone();
+        config.last_project = Some(Uuid::nil());
+        config.write_changes_at_dir(&original, &config_dir).unwrap();
+
+        let read_config = CliConfig::read_from_file_at_dir(&config_dir).unwrap();
+        assert_eq!(read_config.last_project, Some(Uuid::nil()));
+        assert_eq!(read_config.auth.unwrap().access_token, "other_token");
+        assert_eq!(
+            read_config.platform_url.as_deref(),
+            Some("https://old.example")
+        );
+        assert!(!config_dir.join(LOCK_FILE).exists());
+    }
+
+    #[test]
+    fn test_write_changes_keeps_newer_config() {
+        let (config_dir, _temp_dir) = setup_config_dir();
+        fs::create_dir_all(&config_dir).unwrap();
+        let newer = format!("version = {}\n", CONFIG_VERSION + 1);
+        fs::write(config_dir.join(CONFIG_FILE), &newer).unwrap();
+
+        let original = CliConfig::default();
+        let config = CliConfig {
+            last_project: Some(Uuid::nil()),
+            ..Default::default()
+        };
+        let result = config.write_changes_at_dir(&original, &config_dir);
+
+        assert!(matches!(result, Err(ConfigError::UnsupportedVersion(_))));
+        assert_eq!(
+            fs::read_to_string(config_dir.join(CONFIG_FILE)).unwrap(),
+            newer
+        );
+        assert!(!config_dir.join(LOCK_FILE).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_changes_keeps_config_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (config_dir, _temp_dir) = setup_config_dir();
+        let original = CliConfig::default();
+        let config = CliConfig {
+            last_project: Some(Uuid::nil()),
+            ..Default::default()
+        };
+        config.write_changes_at_dir(&original, &config_dir).unwrap();
+
+        let mode = fs::metadata(config_dir.join(CONFIG_FILE))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_write_changes_skips_unchanged_config() {
+        let (config_dir, _temp_dir) = setup_config_dir();
+        let config = CliConfig::default();
+        config.write_changes_at_dir(&config, &config_dir).unwrap();
+        assert!(!config_dir.join(CONFIG_FILE).exists());
+    }
+
+    #[test]
+    fn test_concurrent_writers_do_not_clobber() {
+        let (config_dir, _temp_dir) = setup_config_dir();
+        let original = CliConfig::default();
+
+        let writers = [
+            CliConfig {
+                platform_url: Some("https://platform.example".to_string()),
+                ..Default::default()
+            },
+            CliConfig {
+                last_project: Some(Uuid::nil()),
+                ..Default::default()
+            },
+        ]
+        .map(|config| {
+            let original = original.clone();
+            let config_dir = config_dir.clone();
+            std::thread::spawn(move || config.write_changes_at_dir(&original, &config_dir))
+        });
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        let read_config = CliConfig::read_from_file_at_dir(&config_dir).unwrap();
+        assert_eq!(
+            read_config.platform_url.as_deref(),
+            Some("https://platform.example")
+        );
+        assert_eq!(read_config.last_project, Some(Uuid::nil()));
+    }
+
+    #[test]
+    fn test_stale_lock_is_removed() {
+        let (config_dir, _temp_dir) = setup_config_dir();
+        fs::create_dir_all(&config_dir).unwrap();
+        let lock = fs::File::create(config_dir.join(LOCK_FILE)).unwrap();
+        lock.set_modified(std::time::SystemTime::now() - STALE_LOCK_AGE * 2)
+            .unwrap();
+
+        assert!(
+            CliConfig::default()
+                .write_to_file_at_dir(&config_dir)
+                .is_ok()
+        );
+    }
+
     #[test]
     fn test_write_and_read_config() {
         let (config_dir, _temp_dir) = setup_config_dir();
@@ -430,7 +1362,9 @@ mod tests {
                 user_id: None,
                 wallet_address: None,
                 email: None,
+                platform_url: None,
             }),
+            ..Default::default()
         };
 
         // Test writing
@@ -461,6 +1395,19 @@ mod tests {
         assert!(formatted_cfg.contains("Refresh Token: [Set]"));
     }
 
+    #[test]
+    fn test_resolve_config_dir_prefers_cli_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let args = CliArgs {
+            config_dir: Some(temp_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        assert_eq!(
+            CliConfig::resolve_config_dir(&args).unwrap(),
+            temp_dir.path()
+        );
+    }
+
     #[test]
     fn test_read_nonexistent_config() {
         let (config_dir, _temp_dir) = setup_config_dir();
@@ -479,6 +1426,7 @@ mod tests {
             user_id: None,
             wallet_address: None,
             email: Some("test@example.com".to_string()),
+            platform_url: None,
         };
 
         let display = format!("{auth}");
@@ -499,6 +1447,7 @@ mod tests {
             expires_at: expires,
             wallet_address: Some(Address::from_slice(&[1; 20])),
             email: Some("test@example.com".to_string()),
+            platform_url: None,
             user_id: Some(Uuid::nil()),
         };
         assert_eq!(
@@ -513,6 +1462,7 @@ mod tests {
             expires_at: expires,
             wallet_address: None,
             email: Some("test@example.com".to_string()),
+            platform_url: None,
             user_id: Some(Uuid::nil()),
         };
         assert_eq!(with_email.display_name(), "test@example.com");
@@ -524,6 +1474,7 @@ mod tests {
             expires_at: expires,
             wallet_address: None,
             email: None,
+            platform_url: None,
             user_id: Some(Uuid::nil()),
         };
         assert_eq!(
@@ -538,6 +1489,7 @@ mod tests {
             expires_at: expires,
             wallet_address: None,
             email: None,
+            platform_url: None,
             user_id: None,
         };
         assert_eq!(bare.display_name(), "unknown");
@@ -547,9 +1499,9 @@ mod tests {
     fn test_config_args_show() {
         let mut config = CliConfig::default();
         let args = ConfigArgs {
-            command: ConfigCommand::Show,
+            command: ConfigCommand::Show { json: false },
         };
-        assert!(args.run(&mut config).is_ok());
+        assert!(args.run(&CliArgs::default(), &mut config).is_ok());
     }
 
     #[test]
@@ -562,15 +1514,281 @@ mod tests {
                 user_id: None,
                 wallet_address: None,
                 email: None,
+                platform_url: None,
             }),
+            ..Default::default()
         };
         let args = ConfigArgs {
-            command: ConfigCommand::Delete,
+            command: ConfigCommand::Delete {
+                yes: true,
+                auth_only: false,
+            },
         };
-        assert!(args.run(&mut config).is_ok());
+        assert!(args.run(&CliArgs::default(), &mut config).is_ok());
         assert!(config.auth.is_none());
     }
 
+    #[test]
+    fn test_config_args_delete_auth_only_keeps_settings() {
+        let mut config = CliConfig {
+            platform_url: Some("https://staging.example.com".to_string()),
+            auth: Some(UserAuth::default()),
+            ..Default::default()
+        };
+        let args = ConfigArgs {
+            command: ConfigCommand::Delete {
+                yes: true,
+                auth_only: true,
+            },
+        };
+        assert!(args.run(&CliArgs::default(), &mut config).is_ok());
+        assert!(config.auth.is_none());
+        assert_eq!(
+            config.platform_url.as_deref(),
+            Some("https://staging.example.com")
+        );
+    }
+
+    #[test]
+    fn test_config_args_export_and_import() {
+        let temp_dir = TempDir::new().unwrap();
+        let export_path = temp_dir.path().join("exported.toml");
+
+        let mut config = CliConfig {
+            auth: Some(UserAuth {
+                access_token: "exported".to_string(),
+                refresh_token: "exported_refresh".to_string(),
+                expires_at: DateTime::from_timestamp(1672502400, 0).unwrap(),
+                user_id: None,
+                wallet_address: None,
+                email: Some("exported@example.com".to_string()),
+                platform_url: None,
+            }),
+            ..Default::default()
+        };
+        ConfigArgs {
+            command: ConfigCommand::Export {
+                path: Some(export_path.clone()),
+                force: false,
+            },
+        }
+        .run(&CliArgs::default(), &mut config)
+        .unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&export_path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // The export holds credentials, so an existing file is only replaced with --force
+        let result = ConfigArgs {
+            command: ConfigCommand::Export {
+                path: Some(export_path.clone()),
+                force: false,
+            },
+        }
+        .run(&CliArgs::default(), &mut config);
+        assert!(matches!(result, Err(ConfigError::ExportExists(_))));
+        ConfigArgs {
+            command: ConfigCommand::Export {
+                path: Some(export_path.clone()),
+                force: true,
+            },
+        }
+        .run(&CliArgs::default(), &mut config)
+        .unwrap();
+
+        let mut imported = CliConfig::default();
+        ConfigArgs {
+            command: ConfigCommand::Import {
+                path: export_path,
+                merge: false,
+            },
+        }
+        .run(&CliArgs::default(), &mut imported)
+        .unwrap();
+
+        let auth = imported.auth.unwrap();
+        assert_eq!(auth.access_token, "exported");
+        assert_eq!(auth.email.as_deref(), Some("exported@example.com"));
+    }
+
+    #[test]
+    fn test_merge_prefers_imported_auth() {
+        let auth = |token: &str| {
+            UserAuth {
+                access_token: token.to_string(),
+                refresh_token: token.to_string(),
+                expires_at: DateTime::from_timestamp(1672502400, 0).unwrap(),
+                user_id: None,
+                wallet_address: None,
+                email: None,
+                platform_url: None,
+            }
+        };
+
+        let mut config = CliConfig {
+            auth: Some(auth("current")),
+            ..Default::default()
+        };
+        config.merge(CliConfig::default());
+        assert_eq!(config.auth.as_ref().unwrap().access_token, "current");
+
+        config.merge(CliConfig {
+            auth: Some(auth("imported")),
+            ..Default::default()
+        });
+        assert_eq!(config.auth.as_ref().unwrap().access_token, "imported");
+    }
+
+    #[test]
+    fn test_platform_url_only_overrides_default() {
+        let config = CliConfig {
+            platform_url: Some("https://staging.example.com".to_string()),
+            ..Default::default()
+        };
+        let default_url: Url = DEFAULT_PLATFORM_URL.parse().unwrap();
+        let explicit_url: Url = "http://localhost:3000".parse().unwrap();
+
+        assert_eq!(
+            config.platform_url(&default_url).as_str(),
+            "https://staging.example.com/"
+        );
+        assert_eq!(config.platform_url(&explicit_url), explicit_url);
+        assert_eq!(CliConfig::default().platform_url(&default_url), default_url);
+    }
+
+    #[test]
+    fn test_project_config_overrides_global_url() {
+        let (config_dir, temp_dir) = setup_config_dir();
+        CliConfig {
+            platform_url: Some("https://global.example.com".to_string()),
+            auth: Some(UserAuth {
+                access_token: "global_token".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+        .write_to_file_at_dir(&config_dir)
+        .unwrap();
+
+        let project_root = temp_dir.path().join("monorepo");
+        let nested = project_root.join("packages").join("assertions");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            project_root.join(PROJECT_CONFIG_FILE),
+            "platform_url = \"https://project.example.com\"\n\n[auth]\naccess_token = \"leaked\"\n",
+        )
+        .unwrap();
+
+        let mut config = CliConfig::read_from_file_at_dir(&config_dir).unwrap();
+        config.project = ProjectConfig::discover(&nested).unwrap();
+
+        let default_url: Url = DEFAULT_PLATFORM_URL.parse().unwrap();
+        let explicit_url: Url = "http://localhost:3000".parse().unwrap();
+        assert_eq!(
+            config.platform_url(&default_url).as_str(),
+            "https://project.example.com/"
+        );
+        assert_eq!(config.platform_url(&explicit_url), explicit_url);
+        assert_eq!(config.auth.as_ref().unwrap().access_token, "global_token");
+        assert_eq!(
+            config.project.as_ref().unwrap().path,
+            project_root.join(PROJECT_CONFIG_FILE)
+        );
+
+        // The project override must not leak into the global config
+        config.write_to_file_at_dir(&config_dir).unwrap();
+        let written = std::fs::read_to_string(config_dir.join(CONFIG_FILE)).unwrap();
+        assert!(written.contains("https://global.example.com"));
+        assert!(!written.contains("project.example.com"));
+        assert!(!written.contains("leaked"));
+
+        assert!(ProjectConfig::discover(&config_dir).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_issuer_mismatch() {
+        let staging: Url = "https://staging.example.com".parse().unwrap();
+        let mut auth = UserAuth::default();
+        assert!(auth.issuer_mismatch(&staging).is_none());
+
+        auth.platform_url = Some("https://staging.example.com/".to_string());
+        assert!(auth.issuer_mismatch(&staging).is_none());
+        assert!(
+            auth.issuer_mismatch(&"https://staging.example.com/api/v1".parse().unwrap())
+                .is_none()
+        );
+
+        let local: Url = "http://localhost:3000".parse().unwrap();
+        assert_eq!(auth.issuer_mismatch(&local), Some(staging));
+
+        // Logins stored before the issuer was recorded still deserialize
+        let legacy: UserAuth = toml::from_str(
+            "access_token = \"a\"\nrefresh_token = \"r\"\nexpires_at = 1672502400\n",
+        )
+        .unwrap();
+        assert!(legacy.platform_url.is_none());
+    }
+
+    #[test]
+    fn test_auth_falls_back_to_env_token() {
+        let mut config = CliConfig::default();
+        assert!(config.auth_or_token(None).is_none());
+        assert!(config.auth_or_token(Some(String::new())).is_none());
+
+        let env_auth = config.auth_or_token(Some("ci_token".to_string())).unwrap();
+        assert_eq!(env_auth.access_token, "ci_token");
+        assert!(!env_auth.is_expired());
+        assert!(config.auth.is_none());
+
+        config.auth = Some(UserAuth {
+            access_token: "stored".to_string(),
+            ..Default::default()
+        });
+        let stored = config.auth_or_token(Some("ci_token".to_string())).unwrap();
+        assert_eq!(stored.access_token, "stored");
+    }
+
+    #[test]
+    fn test_redacted_config_hides_tokens() {
+        let config = CliConfig {
+            auth: Some(UserAuth {
+                access_token: "secret_access".to_string(),
+                refresh_token: String::new(),
+                expires_at: DateTime::from_timestamp(1672502400, 0).unwrap(),
+                user_id: None,
+                wallet_address: None,
+                email: Some("user@example.com".to_string()),
+                platform_url: None,
+            }),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(config.redacted()).unwrap();
+        assert_eq!(json["auth"]["access_token"], "[Set]");
+        assert_eq!(json["auth"]["refresh_token"], "[Unset]");
+        assert_eq!(json["auth"]["user"], "user@example.com");
+        assert_eq!(json["auth"]["expired"], true);
+        assert!(!json.to_string().contains("secret_access"));
+    }
+
+    #[test]
+    fn test_import_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = CliConfig::default();
+        let result = ConfigArgs {
+            command: ConfigCommand::Import {
+                path: temp_dir.path().join("missing.toml"),
+                merge: true,
+            },
+        }
+        .run(&CliArgs::default(), &mut config);
+        assert!(matches!(result, Err(ConfigError::ReadError(_))));
+    }
+
     #[test]
     fn test_write_to_file_permission_error() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -603,6 +1821,112 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_validate_file() {
+        let (config_dir, _temp_dir) = setup_config_dir();
+        let config_file = config_dir.join(CONFIG_FILE);
+        assert!(CliConfig::validate_file(&config_file).is_empty());
+
+        fs::create_dir_all(&config_dir).unwrap();
+        fs::write(&config_file, "invalid toml content").unwrap();
+        assert_eq!(CliConfig::validate_file(&config_file).len(), 1);
+
+        fs::write(
+            &config_file,
+            "version = 1\nplatform_url = \"not a url\"\n\n[auth]\naccess_token = \"token\"\nrefresh_token = \"\"\nexpires_at = 1672502400\nwallet_address = \"0x1234\"\n",
+        )
+        .unwrap();
+        assert_eq!(CliConfig::validate_file(&config_file).len(), 1);
+
+        fs::write(
+            &config_file,
+            "version = 1\nplatform_url = \"not a url\"\n\n[auth]\naccess_token = \"token\"\nrefresh_token = \"\"\nexpires_at = 1672502400\n",
+        )
+        .unwrap();
+        let problems = CliConfig::validate_file(&config_file);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[1].contains("expired"));
+    }
+
+    #[test]
+    fn test_assertion_extensions_precedence() {
+        let mut config = CliConfig::default();
+        assert!(config.assertion_extensions().is_empty());
+
+        config.assertion_extensions = Some(vec![".assert.sol".to_string()]);
+        assert_eq!(config.assertion_extensions(), [".assert.sol"]);
+
+        config.project = Some(ProjectConfig {
+            assertion_extensions: Some(vec![".guard.sol".to_string()]),
+            ..Default::default()
+        });
+        assert_eq!(config.assertion_extensions(), [".guard.sol"]);
+    }
+
+    #[test]
+    fn test_malformed_project_config_is_ignored() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join(PROJECT_CONFIG_FILE),
+            "platform_url = [",
+        )
+        .unwrap();
+
+        assert!(ProjectConfig::discover(temp_dir.path()).is_err());
+        assert!(CliConfig::discover_project(temp_dir.path()).is_none());
+    }
+
+    #[test]
+    fn test_validate_project_config() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(ProjectConfig::validate(temp_dir.path()).is_empty());
+
+        fs::write(
+            temp_dir.path().join(PROJECT_CONFIG_FILE),
+            "platform_url = \"not a url\"\n",
+        )
+        .unwrap();
+        assert_eq!(ProjectConfig::validate(temp_dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn test_read_unversioned_config_migrates() {
+        let (config_dir, _temp_dir) = setup_config_dir();
+        let config_file = config_dir.join(CONFIG_FILE);
+        fs::create_dir_all(&config_dir).unwrap();
+        fs::write(
+            &config_file,
+            "platform_url = \"https://staging.example.com\"\n\n[auth]\naccess_token = \"v0_token\"\nrefresh_token = \"\"\nexpires_at = 1672502400\n",
+        )
+        .unwrap();
+
+        let config = CliConfig::read_from_file_at_dir(&config_dir).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.auth.unwrap().access_token, "v0_token");
+        assert_eq!(
+            config.platform_url.as_deref(),
+            Some("https://staging.example.com")
+        );
+
+        let written = fs::read_to_string(&config_file).unwrap();
+        assert!(written.contains(&format!("version = {CONFIG_VERSION}")));
+        assert!(written.contains("v0_token"));
+    }
+
+    #[test]
+    fn test_read_newer_config_version_fails() {
+        let (config_dir, _temp_dir) = setup_config_dir();
+        fs::create_dir_all(&config_dir).unwrap();
+        fs::write(
+            config_dir.join(CONFIG_FILE),
+            format!("version = {}\n", CONFIG_VERSION + 1),
+        )
+        .unwrap();
+
+        let result = CliConfig::read_from_file_at_dir(&config_dir);
+        assert!(matches!(result, Err(ConfigError::UnsupportedVersion(_))));
+    }
+
     #[test]
     fn test_display_empty_config() {
         let config = CliConfig::default();
@@ -619,6 +1943,7 @@ mod tests {
             user_id: Some(Uuid::parse_str("550e8400-e29b-41d4-a716-446655440000").unwrap()),
             wallet_address: Some(Address::from_slice(&[0; 20])),
             email: Some("test@example.com".to_string()),
+            platform_url: None,
         };
 
         let serialized = toml::to_string(&auth).unwrap();
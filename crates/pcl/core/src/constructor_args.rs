@@ -0,0 +1,340 @@
+//! Constructor argument parsing and ABI encoding shared by `apply` and `verify`.
+
+use alloy_dyn_abi::{
+    DynSolType,
+    DynSolValue,
+};
+use alloy_json_abi::{
+    Constructor,
+    JsonAbi,
+    Param,
+    StateMutability,
+};
+use serde_json::Value;
+use std::{
+    borrow::Cow,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+use thiserror::Error;
+
+/// Errors that can occur while parsing constructor arguments against an ABI.
+#[derive(Error, Debug)]
+pub enum ConstructorArgsError {
+    #[error("contract has no constructor but arguments were provided")]
+    NoConstructor,
+
+    #[error(
+        "expected {expected} constructor argument{}, got {got}",
+        if *expected == 1 { "" } else { "s" }
+    )]
+    CountMismatch { expected: usize, got: usize },
+
+    #[error("unsupported type '{ty}' for constructor argument {index}: {reason}")]
+    UnsupportedType {
+        index: usize,
+        ty: String,
+        reason: String,
+    },
+
+    #[error("failed to parse constructor argument {index} '{value}' as {expected_type}: {reason}")]
+    ConstructorArgParse {
+        index: usize,
+        expected_type: String,
+        value: String,
+        reason: String,
+    },
+
+    #[error("failed to read constructor arguments file {}: {source}", path.display())]
+    ArgsFileRead {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to parse constructor arguments file {}: {reason}", path.display())]
+    ArgsFileParse { path: PathBuf, reason: String },
+
+    #[error("invalid constructor signature '{signature}': {reason}")]
+    InvalidSignature { signature: String, reason: String },
+}
+
+/// Returns `abi` with its constructor replaced by one parsed from `signature`.
+///
+/// Used when the compiled constructor differs from the one the assertion is
+/// deployed with, e.g. behind a proxy. The signature is written as
+/// `constructor(address,uint256)`; the `constructor` prefix is optional.
+pub fn with_constructor_signature<'a>(
+    abi: &'a JsonAbi,
+    signature: Option<&str>,
+) -> Result<Cow<'a, JsonAbi>, ConstructorArgsError> {
+    let Some(signature) = signature else {
+        return Ok(Cow::Borrowed(abi));
+    };
+    let invalid = |reason: String| {
+        ConstructorArgsError::InvalidSignature {
+            signature: signature.to_string(),
+            reason,
+        }
+    };
+
+    let params = signature.trim();
+    let params = params
+        .strip_prefix("constructor")
+        .unwrap_or(params)
+        .trim_start();
+    if !params.starts_with('(') {
+        return Err(invalid("expected `constructor(<types>)`".to_string()));
+    }
+    let DynSolType::Tuple(types) = DynSolType::parse(params).map_err(|e| invalid(e.to_string()))?
+    else {
+        return Err(invalid(
+            "expected a parenthesized list of types".to_string(),
+        ));
+    };
+
+    let mut abi = abi.clone();
+    abi.constructor = Some(Constructor {
+        inputs: types
+            .iter()
+            .map(|ty| {
+                Param {
+                    ty: ty.sol_type_name().into_owned(),
+                    name: String::new(),
+                    components: vec![],
+                    internal_type: None,
+                }
+            })
+            .collect(),
+        state_mutability: StateMutability::NonPayable,
+    });
+    Ok(Cow::Owned(abi))
+}
+
+/// Parses each argument against the matching constructor input type.
+///
+/// A contract without a constructor accepts an empty argument list.
+pub fn parse_constructor_args(
+    abi: &JsonAbi,
+    args: &[String],
+) -> Result<Vec<DynSolValue>, ConstructorArgsError> {
+    let Some(constructor) = abi.constructor.as_ref() else {
+        if args.is_empty() {
+            return Ok(Vec::new());
+        }
+        return Err(ConstructorArgsError::NoConstructor);
+    };
+
+    if constructor.inputs.len() != args.len() {
+        return Err(ConstructorArgsError::CountMismatch {
+            expected: constructor.inputs.len(),
+            got: args.len(),
+        });
+    }
+
+    constructor
+        .inputs
+        .iter()
+        .zip(args.iter())
+        .enumerate()
+        .map(|(index, (param, arg))| {
+            let expected_type = param.selector_type();
+            let sol_type: DynSolType = expected_type.parse().map_err(|e| {
+                ConstructorArgsError::UnsupportedType {
+                    index,
+                    ty: expected_type.to_string(),
+                    reason: format!("{e}"),
+                }
+            })?;
+            sol_type.coerce_str(arg).map_err(|e| {
+                ConstructorArgsError::ConstructorArgParse {
+                    index,
+                    expected_type: expected_type.to_string(),
+                    value: arg.clone(),
+                    reason: format!("{e}"),
+                }
+            })
+        })
+        .collect()
+}
+
+/// Reads constructor arguments from a file.
+///
+/// The file is either a JSON array, whose string elements are used verbatim and
+/// other elements in their JSON form, or plain text with one argument per line.
+/// In plain text, blank lines and lines starting with `#` are skipped.
+pub fn read_args_file(path: &Path) -> Result<Vec<String>, ConstructorArgsError> {
+    let contents = std::fs::read_to_string(path).map_err(|source| {
+        ConstructorArgsError::ArgsFileRead {
+            path: path.to_path_buf(),
+            source,
+        }
+    })?;
+
+    if contents.trim_start().starts_with('[') {
+        let values: Vec<Value> = serde_json::from_str(&contents).map_err(|e| {
+            ConstructorArgsError::ArgsFileParse {
+                path: path.to_path_buf(),
+                reason: e.to_string(),
+            }
+        })?;
+        return Ok(values
+            .into_iter()
+            .map(|value| {
+                match value {
+                    Value::String(s) => s,
+                    other => other.to_string(),
+                }
+            })
+            .collect());
+    }
+
+    Ok(contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(ToString::to_string)
+        .collect())
+}
+
+/// Parses and ABI-encodes constructor arguments for appending to creation bytecode.
+pub fn encode_constructor_args(
+    abi: &JsonAbi,
+    args: &[String],
+) -> Result<Vec<u8>, ConstructorArgsError> {
+    let values = parse_constructor_args(abi, args)?;
+    Ok(DynSolValue::Tuple(values).abi_encode_params())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abi_with_inputs(types: &[&str]) -> JsonAbi {
+        JsonAbi {
+            constructor: Some(Constructor {
+                inputs: types
+                    .iter()
+                    .map(|ty| {
+                        Param {
+                            ty: (*ty).to_string(),
+                            name: String::new(),
+                            components: vec![],
+                            internal_type: None,
+                        }
+                    })
+                    .collect(),
+                state_mutability: StateMutability::NonPayable,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_constructor_args_accepts_no_constructor_without_args() {
+        let values = parse_constructor_args(&JsonAbi::default(), &[]).unwrap();
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn parse_constructor_args_reports_offending_index() {
+        let abi = abi_with_inputs(&["uint256", "address"]);
+        let err = parse_constructor_args(&abi, &["1".to_string(), "0xnotanaddress".to_string()])
+            .unwrap_err();
+        match err {
+            ConstructorArgsError::ConstructorArgParse {
+                index,
+                expected_type,
+                value,
+                ..
+            } => {
+                assert_eq!(index, 1);
+                assert_eq!(expected_type, "address");
+                assert_eq!(value, "0xnotanaddress");
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+    }
+
+    #[test]
+    fn read_args_file_supports_lines_and_json() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+
+        let lines = temp_dir.path().join("args.txt");
+        std::fs::write(
+            &lines,
+            "# owner\n0x0000000000000000000000000000000000000001\n\n42\n",
+        )
+        .unwrap();
+        assert_eq!(
+            read_args_file(&lines).unwrap(),
+            vec!["0x0000000000000000000000000000000000000001", "42"]
+        );
+
+        let json = temp_dir.path().join("args.json");
+        std::fs::write(&json, r#"["0x01", 42, [1, 2]]"#).unwrap();
+        assert_eq!(read_args_file(&json).unwrap(), vec!["0x01", "42", "[1,2]"]);
+
+        let missing = temp_dir.path().join("missing.txt");
+        assert!(matches!(
+            read_args_file(&missing),
+            Err(ConstructorArgsError::ArgsFileRead { .. })
+        ));
+    }
+
+    #[test]
+    fn constructor_signature_overrides_abi() {
+        let abi = abi_with_inputs(&["address"]);
+        let args = [
+            "0x0000000000000000000000000000000000000001".to_string(),
+            "42".to_string(),
+        ];
+        assert!(matches!(
+            parse_constructor_args(&abi, &args),
+            Err(ConstructorArgsError::CountMismatch {
+                expected: 1,
+                got: 2
+            })
+        ));
+
+        let overridden =
+            with_constructor_signature(&abi, Some("constructor(address,uint256)")).unwrap();
+        assert_eq!(parse_constructor_args(&overridden, &args).unwrap().len(), 2);
+
+        let tuple = with_constructor_signature(&abi, Some("((uint256,address),bool)")).unwrap();
+        assert_eq!(tuple.constructor.as_ref().unwrap().inputs.len(), 2);
+
+        assert!(matches!(
+            with_constructor_signature(&abi, None).unwrap(),
+            Cow::Borrowed(_)
+        ));
+        for signature in [
+            "constructor(address,",
+            "initialize(address)",
+            "constructor(uint7)",
+        ] {
+            assert!(
+                matches!(
+                    with_constructor_signature(&abi, Some(signature)),
+                    Err(ConstructorArgsError::InvalidSignature { .. })
+                ),
+                "{signature} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_constructor_args_rejects_missing_args() {
+        let abi = abi_with_inputs(&["uint256"]);
+        let err = parse_constructor_args(&abi, &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            ConstructorArgsError::CountMismatch {
+                expected: 1,
+                got: 0
+            }
+        ));
+    }
+}
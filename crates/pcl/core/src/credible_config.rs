@@ -1,5 +1,7 @@
 //! Shared types and parsing for `credible.toml` deployment configuration files.
 
+use alloy_primitives::Address;
+use pcl_common::strip_assertion_extension;
 use serde::Deserialize;
 use serde_json::Value;
 use std::{
@@ -54,20 +56,35 @@ impl CredibleToml {
 
     /// Runs all config validations.
     fn validate(&self) -> Result<(), CredibleConfigError> {
+        self.validate_addresses()?;
         self.validate_unique_addresses()
     }
 
-    /// Ensures no two contracts share the same address.
+    /// Ensures every contract address is a valid Ethereum address.
+    pub(crate) fn validate_addresses(&self) -> Result<(), CredibleConfigError> {
+        for (key, contract) in &self.contracts {
+            contract.address.parse::<Address>().map_err(|e| {
+                CredibleConfigError::Invalid(format!(
+                    "invalid address `{}` for contract `{key}`: {e}",
+                    contract.address
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Ensures no two contracts share the same address, ignoring hex case.
     pub(crate) fn validate_unique_addresses(&self) -> Result<(), CredibleConfigError> {
-        let mut seen: HashMap<&str, &str> = HashMap::new();
+        let mut seen: HashMap<String, &str> = HashMap::new();
         for (key, contract) in &self.contracts {
-            if let Some(existing_key) = seen.get(contract.address.as_str()) {
+            let normalized = contract.address.to_ascii_lowercase();
+            if let Some(existing_key) = seen.get(&normalized) {
                 return Err(CredibleConfigError::Invalid(format!(
                     "duplicate contract address {}: used by both `{}` and `{}`",
                     contract.address, existing_key, key
                 )));
             }
-            seen.insert(&contract.address, key);
+            seen.insert(normalized, key);
         }
         Ok(())
     }
@@ -87,6 +104,11 @@ pub struct CredibleAssertion {
     pub file: String,
     #[serde(default, deserialize_with = "deserialize_args")]
     pub args: Vec<String>,
+    /// Constructor signature to encode `args` with instead of the compiled ABI's,
+    /// e.g. `constructor(address,uint256)`. Only `pcl verify` honors it; `pcl apply`
+    /// rejects it because the release payload cannot carry it.
+    #[serde(default)]
+    pub constructor_signature: Option<String>,
 }
 
 fn deserialize_args<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
@@ -114,7 +136,12 @@ fn value_to_string(value: Value) -> String {
 /// - `file.sol:ContractName` -> `ContractName`
 /// - `ContractName.a.sol` -> `ContractName`
 /// - `ContractName.sol` -> `ContractName`
-pub fn assertion_contract_name(file: &str) -> Result<String, CredibleConfigError> {
+///
+/// `extensions` replaces the default `.a.sol`/`.sol` suffixes when not empty.
+pub fn assertion_contract_name(
+    file: &str,
+    extensions: &[String],
+) -> Result<String, CredibleConfigError> {
     if let Some((_, contract_name)) = file.rsplit_once(':') {
         return Ok(contract_name.to_string());
     }
@@ -126,10 +153,8 @@ pub fn assertion_contract_name(file: &str) -> Result<String, CredibleConfigError
             CredibleConfigError::Invalid(format!("Invalid assertion file path: {file}"))
         })?;
 
-    for suffix in [".a.sol", ".sol"] {
-        if let Some(contract_name) = file_name.strip_suffix(suffix) {
-            return Ok(contract_name.to_string());
-        }
+    if let Some(contract_name) = strip_assertion_extension(file_name, extensions) {
+        return Ok(contract_name.to_string());
     }
 
     Err(CredibleConfigError::Invalid(format!(
@@ -155,13 +180,21 @@ mod tests {
     #[test]
     fn infers_assertion_contract_name_from_solidity_path() {
         assert_eq!(
-            assertion_contract_name("assertions/src/MockAssertion.a.sol").unwrap(),
+            assertion_contract_name("assertions/src/MockAssertion.a.sol", &[]).unwrap(),
             "MockAssertion"
         );
         assert_eq!(
-            assertion_contract_name("assertions/src/Other.sol:NamedAssertion").unwrap(),
+            assertion_contract_name("assertions/src/Other.sol:NamedAssertion", &[]).unwrap(),
             "NamedAssertion"
         );
+        assert_eq!(
+            assertion_contract_name(
+                "assertions/src/Guard.assert.sol",
+                &[".assert.sol".to_string()]
+            )
+            .unwrap(),
+            "Guard"
+        );
     }
 
     #[test]
@@ -210,6 +243,42 @@ mod tests {
         assert!(msg.contains("0xD1f444eA1D2d9fA567F8fD73b15199F90e630074"));
     }
 
+    #[test]
+    fn rejects_invalid_contract_address() {
+        let toml_str = r#"
+            environment = "production"
+            [contracts.ownable]
+            address = "0xD1f444eA1D2d9fA567F8fD73b15199F90e63007"
+            name = "Ownable"
+            [[contracts.ownable.assertions]]
+            file = "src/OwnableAssertion.a.sol"
+        "#;
+        let credible: CredibleToml = toml::from_str(toml_str).unwrap();
+        let msg = credible.validate_addresses().unwrap_err().to_string();
+        assert!(msg.contains("`ownable`"), "unexpected error: {msg}");
+        assert!(msg.contains("0xD1f444eA1D2d9fA567F8fD73b15199F90e63007"));
+    }
+
+    #[test]
+    fn rejects_duplicate_addresses_differing_in_case() {
+        let toml_str = r#"
+            environment = "production"
+            [contracts.ownable]
+            address = "0xD1f444eA1D2d9fA567F8fD73b15199F90e630074"
+            name = "Ownable"
+            [[contracts.ownable.assertions]]
+            file = "src/OwnableAssertion.a.sol"
+
+            [contracts.ownable2]
+            address = "0xd1f444ea1d2d9fa567f8fd73b15199f90e630074"
+            name = "Ownable2"
+            [[contracts.ownable2.assertions]]
+            file = "src/OwnableAssertion.a.sol"
+        "#;
+        let credible: CredibleToml = toml::from_str(toml_str).unwrap();
+        assert!(credible.validate_unique_addresses().is_err());
+    }
+
     #[test]
     fn accepts_distinct_contract_addresses() {
         let toml_str = r#"
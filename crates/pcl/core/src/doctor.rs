@@ -0,0 +1,464 @@
+//! Environment checks for `pcl doctor`.
+//!
+//! Runs every check independently and prints a checklist with remediation
+//! hints, so a single invocation surfaces all setup problems at once.
+
+use crate::{
+    DEFAULT_PLATFORM_URL,
+    config::{
+        ACCESS_TOKEN_ENV,
+        CliConfig,
+    },
+};
+use colored::Colorize;
+use pcl_common::args::CliArgs;
+use serde::Serialize;
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    time::Duration,
+};
+
+/// Timeout for the platform reachability check
+const REACHABILITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(clap::Parser, Debug)]
+#[command(
+    name = "doctor",
+    about = "Check the local environment for common setup problems"
+)]
+pub struct DoctorArgs {
+    #[arg(long, help = "Emit machine-readable output for this command")]
+    pub json: bool,
+
+    #[arg(
+        short = 'u',
+        long = "api-url",
+        env = "PCL_API_URL",
+        value_hint = clap::ValueHint::Url,
+        default_value = DEFAULT_PLATFORM_URL,
+        help = "Base URL for the platform API"
+    )]
+    pub api_url: url::Url,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DoctorError {
+    #[error("{0} check{} failed", if *.0 == 1 { "" } else { "s" })]
+    ChecksFailed(usize),
+
+    #[error("Failed to encode JSON output: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    /// Not required for pcl to work, but likely to cause confusion
+    Warn,
+    Fail,
+}
+
+/// A single line of the `pcl doctor` checklist
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn problem(
+        name: &'static str,
+        status: CheckStatus,
+        detail: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DoctorJsonOutput<'a> {
+    status: &'static str,
+    checks: &'a [Check],
+}
+
+impl DoctorArgs {
+    /// Runs all checks and prints the checklist.
+    ///
+    /// Fails when any check fails; warnings are reported but do not fail.
+    pub async fn run(&self, cli_args: &CliArgs, config: &CliConfig) -> Result<(), DoctorError> {
+        let root = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+        let checks = vec![
+            check_config_dir(cli_args),
+            check_foundry_project(&root),
+            check_forge_binary(std::env::var_os("PATH").as_deref()),
+            check_forge_version(std::env::var_os("PATH").as_deref()),
+            check_auth(config),
+            check_platform(&config.platform_url(&self.api_url)).await,
+        ];
+        let failed = checks
+            .iter()
+            .filter(|check| check.status == CheckStatus::Fail)
+            .count();
+
+        if self.json || cli_args.json_output() {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&DoctorJsonOutput {
+                    status: if failed == 0 { "ok" } else { "failed" },
+                    checks: &checks,
+                })?
+            );
+        } else {
+            for check in &checks {
+                let marker = match check.status {
+                    CheckStatus::Pass => "✅",
+                    CheckStatus::Warn => "⚠️ ",
+                    CheckStatus::Fail => "❌",
+                };
+                println!("{marker} {}: {}", check.name.bold(), check.detail);
+                if let Some(hint) = &check.hint {
+                    println!("   {}", hint.dimmed());
+                }
+            }
+        }
+
+        if failed > 0 {
+            return Err(DoctorError::ChecksFailed(failed));
+        }
+        Ok(())
+    }
+}
+
+/// The config directory must be writable to store credentials.
+fn check_config_dir(cli_args: &CliArgs) -> Check {
+    const NAME: &str = "Config directory";
+    let dir = match CliConfig::resolve_config_dir(cli_args) {
+        Ok(dir) => dir,
+        Err(e) => {
+            return Check::problem(
+                NAME,
+                CheckStatus::Fail,
+                e.to_string(),
+                "Set XDG_CONFIG_HOME to a writable directory",
+            );
+        }
+    };
+    match CliConfig::ensure_writable_directory(&dir) {
+        Ok(()) => Check::pass(NAME, format!("{} is writable", dir.display())),
+        Err(e) => {
+            Check::problem(
+                NAME,
+                CheckStatus::Fail,
+                format!("{}: {e}", dir.display()),
+                "Fix the directory permissions or set XDG_CONFIG_HOME to a writable directory",
+            )
+        }
+    }
+}
+
+/// Builds and tests run from a foundry project root.
+fn check_foundry_project(dir: &Path) -> Check {
+    const NAME: &str = "Foundry project";
+    match dir
+        .ancestors()
+        .find(|ancestor| ancestor.join("foundry.toml").is_file())
+    {
+        Some(root) => {
+            Check::pass(
+                NAME,
+                format!("found {}", root.join("foundry.toml").display()),
+            )
+        }
+        None => {
+            Check::problem(
+                NAME,
+                CheckStatus::Warn,
+                format!("no foundry.toml in {} or its parents", dir.display()),
+                "Run pcl from your project root or pass --root to build, test and apply",
+            )
+        }
+    }
+}
+
+/// pcl bundles its own compiler pipeline, but most projects also use forge directly.
+fn check_forge_binary(path_var: Option<&std::ffi::OsStr>) -> Check {
+    const NAME: &str = "Foundry install";
+    match find_forge(path_var) {
+        Some(forge) => Check::pass(NAME, format!("forge found at {}", forge.display())),
+        None => {
+            Check::problem(
+                NAME,
+                CheckStatus::Warn,
+                "forge is not on PATH",
+                "Install foundry with `curl -L https://foundry.paradigm.xyz | bash && foundryup` to manage dependencies",
+            )
+        }
+    }
+}
+
+/// A forge on PATH from another major release can produce artifacts pcl reads differently.
+fn check_forge_version(path_var: Option<&std::ffi::OsStr>) -> Check {
+    const NAME: &str = "Foundry version";
+    let bundled = pcl_phoundry::FOUNDRY_VERSION;
+    let Some(forge) = find_forge(path_var) else {
+        return Check::pass(NAME, format!("pcl bundles foundry {bundled}"));
+    };
+    let output = std::process::Command::new(&forge).arg("--version").output();
+    let installed = output
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|stdout| parse_version(&stdout));
+    compare_foundry_versions(bundled, installed)
+}
+
+fn compare_foundry_versions(bundled: &str, installed: Option<(u64, u64, u64)>) -> Check {
+    const NAME: &str = "Foundry version";
+    let Some((major, minor, patch)) = installed else {
+        return Check::problem(
+            NAME,
+            CheckStatus::Warn,
+            format!("could not read `forge --version`; pcl bundles foundry {bundled}"),
+            "Check that forge runs on its own",
+        );
+    };
+    let installed = format!("{major}.{minor}.{patch}");
+    match parse_version(bundled) {
+        Some((bundled_major, ..)) if bundled_major != major => {
+            Check::problem(
+                NAME,
+                CheckStatus::Warn,
+                format!("forge {installed} on PATH, pcl bundles foundry {bundled}"),
+                "Run `foundryup` to match pcl, since artifacts from another major release may differ",
+            )
+        }
+        _ => {
+            Check::pass(
+                NAME,
+                format!("forge {installed}, pcl bundles foundry {bundled}"),
+            )
+        }
+    }
+}
+
+/// Finds the first `major.minor.patch` version in `text`, e.g. in `forge Version: 1.5.1-stable`.
+fn parse_version(text: &str) -> Option<(u64, u64, u64)> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .find_map(|word| {
+            let mut parts = word.split('.').map(str::parse::<u64>);
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch))) => Some((major, minor, patch)),
+                _ => None,
+            }
+        })
+}
+
+fn find_forge(path_var: Option<&std::ffi::OsStr>) -> Option<PathBuf> {
+    path_var.and_then(|paths| {
+        std::env::split_paths(paths)
+            .map(|dir| dir.join(if cfg!(windows) { "forge.exe" } else { "forge" }))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Commands talking to the platform need a valid, unexpired token.
+fn check_auth(config: &CliConfig) -> Check {
+    const NAME: &str = "Authentication";
+    if config.auth.is_none() && config.auth().is_some() {
+        return Check::pass(NAME, format!("using token from {ACCESS_TOKEN_ENV}"));
+    }
+    match &config.auth {
+        None => {
+            Check::problem(
+                NAME,
+                CheckStatus::Fail,
+                "not logged in",
+                "Run `pcl auth login`",
+            )
+        }
+        Some(auth) if auth.is_expired() => {
+            Check::problem(
+                NAME,
+                CheckStatus::Fail,
+                format!(
+                    "token for {} expired at {}",
+                    auth.display_name(),
+                    auth.expires_at.format("%Y-%m-%d %H:%M:%S UTC")
+                ),
+                "Run `pcl auth login` again",
+            )
+        }
+        Some(auth) => {
+            Check::pass(
+                NAME,
+                format!(
+                    "logged in as {} until {}",
+                    auth.display_name(),
+                    auth.expires_at.format("%Y-%m-%d %H:%M:%S UTC")
+                ),
+            )
+        }
+    }
+}
+
+/// Any HTTP response means the platform is reachable; only transport errors fail.
+async fn check_platform(platform_url: &url::Url) -> Check {
+    const NAME: &str = "Platform";
+    let client = match reqwest::Client::builder()
+        .timeout(REACHABILITY_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            return Check::problem(
+                NAME,
+                CheckStatus::Fail,
+                e.to_string(),
+                "Check your TLS setup",
+            );
+        }
+    };
+    match client.get(platform_url.clone()).send().await {
+        Ok(response) => {
+            Check::pass(
+                NAME,
+                format!("{platform_url} responded with {}", response.status()),
+            )
+        }
+        Err(e) => {
+            Check::problem(
+                NAME,
+                CheckStatus::Fail,
+                format!("{platform_url} is unreachable: {e}"),
+                "Check your network connection, proxy settings and `pcl config set-platform-url`",
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UserAuth;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_check_config_dir_uses_cli_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let cli_args = CliArgs {
+            config_dir: Some(temp_dir.path().join("pcl")),
+            ..Default::default()
+        };
+
+        let check = check_config_dir(&cli_args);
+
+        assert_eq!(check.status, CheckStatus::Pass);
+        assert!(temp_dir.path().join("pcl").is_dir());
+    }
+
+    #[test]
+    fn test_check_foundry_project_searches_parents() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("assertions").join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+        assert_eq!(check_foundry_project(&nested).status, CheckStatus::Warn);
+
+        std::fs::write(temp_dir.path().join("foundry.toml"), "[profile.default]\n").unwrap();
+        assert_eq!(check_foundry_project(&nested).status, CheckStatus::Pass);
+    }
+
+    #[test]
+    fn test_check_forge_binary_without_path() {
+        let empty = TempDir::new().unwrap();
+        let check = check_forge_binary(Some(empty.path().as_os_str()));
+        assert_eq!(check.status, CheckStatus::Warn);
+        assert_eq!(check_forge_binary(None).status, CheckStatus::Warn);
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(
+            parse_version("forge Version: 1.5.1-stable\nCommit SHA: abc"),
+            Some((1, 5, 1))
+        );
+        assert_eq!(
+            parse_version("forge 0.2.0 (d5c3ddd 2024-01-01)"),
+            Some((0, 2, 0))
+        );
+        assert_eq!(parse_version("forge nightly"), None);
+    }
+
+    #[test]
+    fn test_compare_foundry_versions() {
+        assert_eq!(
+            compare_foundry_versions("1.5.1-nightly", Some((1, 3, 0))).status,
+            CheckStatus::Pass
+        );
+        assert_eq!(
+            compare_foundry_versions("1.5.1-nightly", Some((0, 2, 0))).status,
+            CheckStatus::Warn
+        );
+        assert_eq!(
+            compare_foundry_versions("1.5.1-nightly", None).status,
+            CheckStatus::Warn
+        );
+    }
+
+    #[test]
+    fn test_check_auth() {
+        assert_eq!(check_auth(&CliConfig::default()).status, CheckStatus::Fail);
+
+        let mut config = CliConfig {
+            auth: Some(UserAuth {
+                expires_at: chrono::Utc::now() - chrono::Duration::hours(1),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(check_auth(&config).status, CheckStatus::Fail);
+
+        if let Some(auth) = config.auth.as_mut() {
+            auth.expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+        }
+        assert_eq!(check_auth(&config).status, CheckStatus::Pass);
+    }
+
+    #[tokio::test]
+    async fn test_check_platform() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/")
+            .with_status(404)
+            .create_async()
+            .await;
+
+        let url: url::Url = server.url().parse().unwrap();
+        assert_eq!(check_platform(&url).await.status, CheckStatus::Pass);
+        mock.assert_async().await;
+
+        let unreachable: url::Url = "http://127.0.0.1:9".parse().unwrap();
+        assert_eq!(check_platform(&unreachable).await.status, CheckStatus::Fail);
+    }
+}
@@ -6,19 +6,30 @@
 //! output directory.
 
 use crate::{
+    DEFAULT_API_TIMEOUT_SECS,
+    DEFAULT_API_VERSION,
     DEFAULT_PLATFORM_URL,
-    client::authenticated_client,
+    client::{
+        RateLimitError,
+        authenticated_client,
+        parse_api_version,
+        retry_rate_limited,
+    },
     config::CliConfig,
 };
 use dapp_api_client::generated::client::{
     Client as GeneratedClient,
+    Error as ApiError,
     types::GetViewsProjectsProjectIdAssertionsAssertionIdAssertionId,
 };
 use pcl_common::args::CliArgs;
 use serde::Serialize;
-use std::path::{
-    Path,
-    PathBuf,
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    time::Duration,
 };
 use uuid::Uuid;
 
@@ -51,6 +62,24 @@ pub struct DownloadArgs {
         help = "Base URL for the platform API"
     )]
     pub api_url: url::Url,
+
+    #[arg(
+        long = "api-version",
+        env = "PCL_API_VERSION",
+        default_value = DEFAULT_API_VERSION,
+        value_parser = parse_api_version,
+        help = "Platform API version to use (e.g. v1)"
+    )]
+    pub api_version: String,
+
+    #[arg(
+        long = "api-timeout-secs",
+        env = "PCL_API_TIMEOUT_SECS",
+        default_value_t = DEFAULT_API_TIMEOUT_SECS,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Seconds to wait for each platform API request"
+    )]
+    pub api_timeout_secs: u64,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -58,6 +87,9 @@ pub enum DownloadError {
     #[error("Run `pcl auth login` first")]
     NoAuthToken,
 
+    #[error("Authentication token expired. Run `pcl auth login` again.")]
+    TokenExpired,
+
     #[error("--project-id is required")]
     MissingIdentifier,
 
@@ -71,6 +103,9 @@ pub enum DownloadError {
         body: String,
     },
 
+    #[error("Rate limited by {endpoint}; gave up after {retries} retries")]
+    RateLimited { endpoint: String, retries: u32 },
+
     #[error("{message}: {source}")]
     Io {
         message: String,
@@ -106,6 +141,7 @@ struct DownloadedFile {
 impl DownloadArgs {
     pub async fn run(&self, cli_args: &CliArgs, config: &CliConfig) -> Result<(), DownloadError> {
         let json_output = cli_args.json_output() || self.json;
+        let print_progress = !json_output && !cli_args.quiet();
 
         let client = self.build_client(config)?;
 
@@ -119,7 +155,7 @@ impl DownloadArgs {
 
         let output_dir = self.prepare_output_dir(&project_name)?;
 
-        if !json_output {
+        if print_progress {
             println!(
                 "Downloading {} assertion{} for project \"{project_name}\"...\n",
                 assertions.len(),
@@ -128,7 +164,13 @@ impl DownloadArgs {
         }
 
         let (downloaded, skipped) = self
-            .download_assertions(&client, &project_id, &assertions, &output_dir, json_output)
+            .download_assertions(
+                &client,
+                &project_id,
+                &assertions,
+                &output_dir,
+                print_progress,
+            )
             .await?;
 
         Self::print_result(
@@ -189,7 +231,7 @@ impl DownloadArgs {
         project_id: &Uuid,
         assertions: &[dapp_api_client::generated::client::types::GetViewsProjectsProjectIdAssertionsResponseDataAssertionsItem],
         output_dir: &Path,
-        json_output: bool,
+        print_progress: bool,
     ) -> Result<(Vec<DownloadedFile>, usize), DownloadError> {
         let mut downloaded = Vec::new();
         let mut skipped = 0usize;
@@ -223,7 +265,7 @@ impl DownloadArgs {
                     }
                 })?;
 
-                if !json_output {
+                if print_progress {
                     println!("  {file_name}");
                 }
 
@@ -250,7 +292,7 @@ impl DownloadArgs {
                 });
             } else {
                 skipped += 1;
-                if !json_output {
+                if print_progress {
                     println!("  [skipped] {contract_name} — no source code available");
                 }
             }
@@ -292,9 +334,16 @@ impl DownloadArgs {
     }
 
     fn build_client(&self, config: &CliConfig) -> Result<GeneratedClient, DownloadError> {
-        authenticated_client(config, &self.api_url).map_err(|e| {
+        authenticated_client(
+            config,
+            &self.api_url,
+            &self.api_version,
+            Duration::from_secs(self.api_timeout_secs),
+        )
+        .map_err(|e| {
             match e {
                 crate::client::ClientBuildError::NoAuthToken => DownloadError::NoAuthToken,
+                crate::client::ClientBuildError::TokenExpired => DownloadError::TokenExpired,
                 crate::client::ClientBuildError::InvalidConfig(msg) => {
                     DownloadError::InvalidConfig(msg)
                 }
@@ -308,17 +357,11 @@ impl DownloadArgs {
     ) -> Result<(Uuid, String), DownloadError> {
         let pid = self.project_id.ok_or(DownloadError::MissingIdentifier)?;
 
-        let project = client
-            .get_projects_project_id(&pid, None)
+        let endpoint = format!("/projects/{pid}");
+        let project = retry_rate_limited(&endpoint, || client.get_projects_project_id(&pid, None))
             .await
             .map(dapp_api_client::generated::client::ResponseValue::into_inner)
-            .map_err(|e| {
-                DownloadError::Api {
-                    endpoint: format!("/projects/{pid}"),
-                    status: e.status().map(|s| s.as_u16()),
-                    body: e.to_string(),
-                }
-            })?;
+            .map_err(|e| api_error(endpoint, e))?;
 
         Ok((project.project_id, project.project_name.to_string()))
     }
@@ -333,17 +376,13 @@ impl DownloadArgs {
         >,
         DownloadError,
     >{
-        let response = client
-            .get_views_projects_project_id_assertions(project_id, None)
-            .await
-            .map(dapp_api_client::generated::client::ResponseValue::into_inner)
-            .map_err(|e| {
-                DownloadError::Api {
-                    endpoint: format!("/views/projects/{project_id}/assertions"),
-                    status: e.status().map(|s| s.as_u16()),
-                    body: e.to_string(),
-                }
-            })?;
+        let endpoint = format!("/views/projects/{project_id}/assertions");
+        let response = retry_rate_limited(&endpoint, || {
+            client.get_views_projects_project_id_assertions(project_id, None)
+        })
+        .await
+        .map(dapp_api_client::generated::client::ResponseValue::into_inner)
+        .map_err(|e| api_error(endpoint, e))?;
 
         Ok(response.data.assertions)
     }
@@ -360,22 +399,34 @@ impl DownloadArgs {
         let aid = GetViewsProjectsProjectIdAssertionsAssertionIdAssertionId::try_from(assertion_id)
             .map_err(|e| DownloadError::InvalidConfig(format!("Invalid assertion ID: {e}")))?;
 
-        let response = client
-            .get_views_projects_project_id_assertions_assertion_id(project_id, &aid)
-            .await
-            .map(dapp_api_client::generated::client::ResponseValue::into_inner)
-            .map_err(|e| {
-                DownloadError::Api {
-                    endpoint: format!("/views/projects/{project_id}/assertions/{assertion_id}"),
-                    status: e.status().map(|s| s.as_u16()),
-                    body: e.to_string(),
-                }
-            })?;
+        let endpoint = format!("/views/projects/{project_id}/assertions/{assertion_id}");
+        let response = retry_rate_limited(&endpoint, || {
+            client.get_views_projects_project_id_assertions_assertion_id(project_id, &aid)
+        })
+        .await
+        .map(dapp_api_client::generated::client::ResponseValue::into_inner)
+        .map_err(|e| api_error(endpoint, e))?;
 
         Ok(response.data)
     }
 }
 
+fn api_error<E>(endpoint: String, err: RateLimitError<E>) -> DownloadError
+where
+    ApiError<E>: std::fmt::Display,
+{
+    match err {
+        RateLimitError::RateLimited { retries } => DownloadError::RateLimited { endpoint, retries },
+        RateLimitError::Request(e) => {
+            DownloadError::Api {
+                endpoint,
+                status: e.status().map(|s| s.as_u16()),
+                body: e.to_string(),
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,7 @@
-use crate::credible_config::CredibleConfigError;
+use crate::{
+    constructor_args::ConstructorArgsError,
+    credible_config::CredibleConfigError,
+};
 use dapp_api_client::generated::client::{
     Error as ApiError,
     types::GetCliAuthStatusResponse,
@@ -13,6 +16,14 @@ pub enum ApplyError {
     #[error("Run `pcl auth login` first")]
     NoAuthToken,
 
+    #[error("Authentication token expired. Run `pcl auth login` again.")]
+    TokenExpired,
+
+    #[error(
+        "Logged in to {issuer} but applying to {target}. Run `pcl auth logout` and `pcl auth login --auth-url {target}`"
+    )]
+    TokenIssuerMismatch { issuer: String, target: String },
+
     #[error("{message}: {source}")]
     Io {
         message: String,
@@ -35,6 +46,22 @@ pub enum ApplyError {
     #[error("Build failed: {0}")]
     BuildFailed(#[source] Box<PhoundryError>),
 
+    #[error("Invalid constructor arguments for {assertion}: {source}")]
+    ConstructorArgs {
+        assertion: String,
+        #[source]
+        source: ConstructorArgsError,
+    },
+
+    #[error(
+        "Flattened source for {assertion} is {size} bytes, over the --max-source-bytes limit of {limit}"
+    )]
+    SourceTooLarge {
+        assertion: String,
+        size: usize,
+        limit: usize,
+    },
+
     #[error("API request to {endpoint} failed{}: {body}", status.map_or(String::new(), |s| format!(" with status {s}")))]
     Api {
         endpoint: String,
@@ -42,6 +69,9 @@ pub enum ApplyError {
         body: String,
     },
 
+    #[error("Rate limited by {endpoint}; gave up after {retries} retries")]
+    RateLimited { endpoint: String, retries: u32 },
+
     #[error("{0}")]
     VerificationFailed(String),
 
@@ -51,6 +81,12 @@ pub enum ApplyError {
     #[error("JSON mode with pending changes requires `--yes`")]
     JsonConfirmationRequiresYes,
 
+    #[error("{prompt} needs an interactive terminal; pass {flag} instead")]
+    PromptRequired {
+        prompt: &'static str,
+        flag: &'static str,
+    },
+
     #[error("Failed to encode JSON output: {0}")]
     Json(#[from] serde_json::Error),
 }
@@ -81,6 +117,9 @@ pub enum VerifyError {
     #[error("Failed to encode constructor arguments: {0}")]
     AbiEncode(String),
 
+    #[error("Failed to encode constructor arguments: {0}")]
+    ConstructorArgs(#[from] ConstructorArgsError),
+
     #[error("Failed to encode JSON output: {0}")]
     Json(#[from] serde_json::Error),
 }
@@ -108,6 +147,51 @@ pub enum ConfigError {
     /// but no authentication token is present in the config
     #[error("No Authentication Token Found")]
     NotAuthenticated,
+
+    /// Error when the config file was written by a newer, incompatible pcl
+    #[error("Config file version {0} is not supported by this version of pcl. Upgrade pcl.")]
+    UnsupportedVersion(i64),
+
+    /// Error when another pcl process holds the config lock for too long
+    #[error(
+        "Timed out waiting for another pcl process to finish writing the config. Remove {0:?} if no other pcl is running"
+    )]
+    LockTimeout(std::path::PathBuf),
+
+    /// Error when the config file's `version` is not an integer
+    #[error("Invalid config file version: {0}")]
+    InvalidVersion(String),
+
+    /// Error when the home directory cannot be resolved and no config
+    /// directory override was provided
+    #[error(
+        "Could not determine the home directory. Set XDG_CONFIG_HOME or pass an explicit config directory."
+    )]
+    NoHomeDirectory,
+
+    /// Error when encoding the configuration as JSON fails
+    #[error("Failed to encode JSON output: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Error when a destructive operation needs confirmation but no terminal is attached
+    #[error("Refusing to delete without confirmation; pass --yes to skip the prompt")]
+    ConfirmationRequired,
+
+    /// Error when the confirmation prompt fails
+    #[error("Confirmation prompt failed: {0}")]
+    PromptFailed(#[source] inquire::InquireError),
+
+    /// Error when the user declines to delete the configuration
+    #[error("Configuration deletion cancelled")]
+    DeleteCancelled,
+
+    /// Error when `pcl config export` would replace an existing file
+    #[error("{0} already exists; pass --force to overwrite it")]
+    ExportExists(std::path::PathBuf),
+
+    /// Error when `pcl config validate` finds problems
+    #[error("{0} config problem{} found", if *.0 == 1 { "" } else { "s" })]
+    ValidationFailed(usize),
 }
 
 /// Errors that can occur during authentication operations
@@ -155,6 +239,14 @@ pub enum AuthError {
     #[error("Authentication failed: {0}")]
     InvalidAuthData(String),
 
+    /// Error when a command requires a logged-in user but none is stored
+    #[error("Not logged in. Please run `pcl auth login` first.")]
+    NotAuthenticated,
+
+    /// Error when the session extension prompt fails
+    #[error("Confirmation prompt failed: {0}")]
+    PromptFailed(#[source] inquire::InquireError),
+
     /// Error when config operations fail during auth
     #[error("Config error: {0}")]
     ConfigError(#[source] ConfigError),
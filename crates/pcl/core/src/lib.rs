@@ -1,3 +1,9 @@
+//! Commands and platform client logic behind `pcl`.
+//!
+//! Releases can be built and submitted without the CLI layer through
+//! [`apply::build_release`], [`apply::preview_release`] and
+//! [`apply::create_release`].
+
 #![allow(clippy::must_use_candidate)]
 #![allow(clippy::missing_errors_doc)]
 #![allow(clippy::struct_field_names)]
@@ -5,10 +11,13 @@
 
 pub mod apply;
 pub mod auth;
+pub mod cache;
 pub mod client;
 pub mod config;
+pub mod constructor_args;
 pub mod credible_config;
 pub mod diff;
+pub mod doctor;
 pub mod download;
 pub mod error;
 #[cfg(feature = "credible")]
@@ -16,3 +25,24 @@ pub mod verify;
 
 /// Default platform url. URL suffixes added on demand.
 pub const DEFAULT_PLATFORM_URL: &str = "https://app.phylax.systems";
+
+/// Default platform API version, used as `{platform_url}/api/{version}`.
+pub const DEFAULT_API_VERSION: &str = "v1";
+
+/// Default timeout for a single platform API request, in seconds.
+pub const DEFAULT_API_TIMEOUT_SECS: u64 = 30;
+
+/// Runs blocking build, prompt or file work off the runtime thread, so a Ctrl-C
+/// arriving meanwhile is handled right away instead of after the work ends.
+///
+/// A panic in `work` is resumed on the caller, as if it had run inline.
+pub async fn run_blocking<T, F>(work: F) -> T
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(work).await {
+        Ok(value) => value,
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
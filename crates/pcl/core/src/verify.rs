@@ -1,4 +1,10 @@
+pub use crate::constructor_args::encode_constructor_args;
 use crate::{
+    config::CliConfig,
+    constructor_args::{
+        read_args_file,
+        with_constructor_signature,
+    },
     credible_config::{
         CredibleConfigError,
         CredibleToml,
@@ -6,10 +12,6 @@ use crate::{
     },
     error::VerifyError,
 };
-use alloy_dyn_abi::{
-    DynSolType,
-    DynSolValue,
-};
 use alloy_json_abi::JsonAbi;
 use alloy_primitives::{
     Bytes,
@@ -23,7 +25,10 @@ use assertion_verification::{
 };
 use clap::ValueHint;
 use pcl_common::args::CliArgs;
-use pcl_phoundry::build_and_flatten::BuildAndFlattenArgs;
+use pcl_phoundry::{
+    build_and_flatten::BuildAndFlattenArgs,
+    compile::ProfileArgs,
+};
 use serde::Serialize;
 use std::path::{
     Path,
@@ -58,6 +63,23 @@ pub struct VerifyArgs {
     #[arg(long, num_args = 1.., help = "Constructor arguments for the assertion")]
     pub args: Vec<String>,
 
+    #[arg(
+        long,
+        value_hint = ValueHint::FilePath,
+        help = "File with constructor arguments, one per line or as a JSON array. Appended after --args"
+    )]
+    pub args_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "SIGNATURE",
+        help = "Encode constructor arguments with this signature instead of the compiled ABI, e.g. \"constructor(address,uint256)\""
+    )]
+    pub constructor_signature: Option<String>,
+
+    #[command(flatten)]
+    pub profile_args: ProfileArgs,
+
     #[arg(long, help = "Emit machine-readable JSON output")]
     pub json: bool,
 }
@@ -75,7 +97,7 @@ pub struct VerifyJsonAssertion {
 }
 
 impl VerifyArgs {
-    pub fn run(&self, cli_args: &CliArgs) -> Result<(), VerifyError> {
+    pub fn run(&self, cli_args: &CliArgs, config: &CliConfig) -> Result<(), VerifyError> {
         let json_output = cli_args.json_output() || self.json;
         let root = std::fs::canonicalize(&self.root).map_err(|e| {
             VerifyError::Io {
@@ -84,15 +106,31 @@ impl VerifyArgs {
             }
         })?;
 
-        if self.assertion.is_none() && !self.args.is_empty() {
+        if self.assertion.is_none()
+            && (!self.args.is_empty()
+                || self.args_file.is_some()
+                || self.constructor_signature.is_some())
+        {
             return Err(VerifyError::Config(CredibleConfigError::Invalid(
-                "--args can only be used when verifying a specific assertion".to_string(),
+                "--args, --args-file and --constructor-signature can only be used when verifying a specific assertion"
+                    .to_string(),
             )));
         }
 
+        let extensions = config.assertion_extensions();
         let inputs = match &self.assertion {
-            Some(assertion) => self.build_single(assertion, &root)?,
-            None => Self::build_from_toml(&root, &self.config)?,
+            Some(assertion) => {
+                self.build_single(assertion, &root, cli_args.verbose(), extensions)?
+            }
+            None => {
+                Self::build_from_toml(
+                    &root,
+                    &self.config,
+                    cli_args.verbose(),
+                    extensions,
+                    &self.profile_args,
+                )?
+            }
         };
 
         let bytecodes: Vec<(&str, Bytes)> = inputs
@@ -130,17 +168,39 @@ impl VerifyArgs {
         Ok(())
     }
 
-    fn build_single(&self, assertion: &str, root: &Path) -> Result<Vec<VerifyInput>, VerifyError> {
+    /// Inline `--args` followed by any arguments read from `--args-file`
+    fn constructor_args(&self) -> Result<Vec<String>, VerifyError> {
+        let mut args = self.args.clone();
+        if let Some(path) = &self.args_file {
+            args.extend(read_args_file(path)?);
+        }
+        Ok(args)
+    }
+
+    fn build_single(
+        &self,
+        assertion: &str,
+        root: &Path,
+        verbose: bool,
+        extensions: &[String],
+    ) -> Result<Vec<VerifyInput>, VerifyError> {
         let contract_name = parse_assertion_name(assertion);
+        // The qualified `file:contract` form disambiguates same-named contracts
         let output = BuildAndFlattenArgs {
             root: Some(root.to_path_buf()),
-            assertion_contract: contract_name.clone(),
+            assertion_contract: assertion.to_string(),
+            verbose,
+            assertion_extensions: extensions.to_vec(),
+            profile_args: self.profile_args.clone(),
+            ..Default::default()
         }
         .run()
         .map_err(VerifyError::BuildFailed)?;
 
-        let bytecode = build_deployment_bytecode(&output.bytecode, &output.abi, &self.args)?;
-        let display_name = format_display_name(&contract_name, &self.args);
+        let args = self.constructor_args()?;
+        let abi = with_constructor_signature(&output.abi, self.constructor_signature.as_deref())?;
+        let bytecode = build_deployment_bytecode(&output.bytecode, &abi, &args)?;
+        let display_name = format_display_name(&contract_name, &args);
 
         Ok(vec![VerifyInput {
             display_name,
@@ -148,23 +208,36 @@ impl VerifyArgs {
         }])
     }
 
-    fn build_from_toml(root: &Path, config: &Path) -> Result<Vec<VerifyInput>, VerifyError> {
+    fn build_from_toml(
+        root: &Path,
+        config: &Path,
+        verbose: bool,
+        extensions: &[String],
+        profile_args: &ProfileArgs,
+    ) -> Result<Vec<VerifyInput>, VerifyError> {
         let config_path = root.join(config);
         let credible = CredibleToml::from_path(&config_path)?;
 
         let mut inputs = Vec::new();
         for contract in credible.contracts.values() {
             for assertion in &contract.assertions {
-                let contract_name = assertion_contract_name(&assertion.file)?;
+                let contract_name = assertion_contract_name(&assertion.file, extensions)?;
                 let output = BuildAndFlattenArgs {
                     root: Some(root.to_path_buf()),
                     assertion_contract: contract_name.clone(),
+                    verbose,
+                    assertion_extensions: extensions.to_vec(),
+                    profile_args: profile_args.clone(),
+                    ..Default::default()
                 }
                 .run()
                 .map_err(VerifyError::BuildFailed)?;
 
-                let bytecode =
-                    build_deployment_bytecode(&output.bytecode, &output.abi, &assertion.args)?;
+                let abi = with_constructor_signature(
+                    &output.abi,
+                    assertion.constructor_signature.as_deref(),
+                )?;
+                let bytecode = build_deployment_bytecode(&output.bytecode, &abi, &assertion.args)?;
                 let display_name = format_display_name(&contract_name, &assertion.args);
 
                 inputs.push(VerifyInput {
@@ -262,43 +335,6 @@ pub fn build_deployment_bytecode(
     Ok(Bytes::from(bytecode))
 }
 
-pub fn encode_constructor_args(abi: &JsonAbi, args: &[String]) -> Result<Vec<u8>, VerifyError> {
-    let constructor = abi.constructor.as_ref().ok_or_else(|| {
-        VerifyError::AbiEncode(
-            "contract has no constructor but arguments were provided".to_string(),
-        )
-    })?;
-
-    if constructor.inputs.len() != args.len() {
-        return Err(VerifyError::AbiEncode(format!(
-            "expected {} constructor argument{}, got {}",
-            constructor.inputs.len(),
-            if constructor.inputs.len() == 1 {
-                ""
-            } else {
-                "s"
-            },
-            args.len()
-        )));
-    }
-
-    let values: Vec<DynSolValue> = constructor
-        .inputs
-        .iter()
-        .zip(args.iter())
-        .map(|(param, arg)| {
-            let sol_type: DynSolType = param.ty.parse().map_err(|e| {
-                VerifyError::AbiEncode(format!("unsupported type '{}': {e}", param.ty))
-            })?;
-            sol_type.coerce_str(arg).map_err(|e| {
-                VerifyError::AbiEncode(format!("failed to parse '{}' as {}: {e}", arg, param.ty))
-            })
-        })
-        .collect::<Result<_, _>>()?;
-
-    Ok(DynSolValue::Tuple(values).abi_encode_params())
-}
-
 pub fn format_display_name(name: &str, args: &[String]) -> String {
     if args.is_empty() {
         name.to_string()
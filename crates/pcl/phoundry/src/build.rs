@@ -6,11 +6,31 @@ use foundry_cli::opts::{
     BuildOpts,
     ProjectPathOpts,
 };
+use foundry_compilers::{
+    Artifact,
+    ProjectCompileOutput,
+};
+use indicatif::{
+    ProgressBar,
+    ProgressStyle,
+};
+use pcl_common::args::CliArgs;
+use serde::Serialize;
 
-use std::path::PathBuf;
+use std::{
+    io::IsTerminal,
+    path::PathBuf,
+    time::Duration,
+};
 
 use crate::{
-    compile::compile,
+    compile::{
+        CompileOptions,
+        ProfileArgs,
+        compile,
+        diagnostics,
+        load_config,
+    },
     error::PhoundryError,
 };
 
@@ -25,16 +45,82 @@ pub struct BuildArgs {
         help = "Root directory of the project"
     )]
     pub root: Option<PathBuf>,
+
+    /// Directory to write build artifacts to
+    #[clap(
+        long,
+        value_hint = ValueHint::DirPath,
+        help = "Directory to write build artifacts to (defaults to the foundry `out` setting)"
+    )]
+    pub output_dir: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub profile_args: ProfileArgs,
+
+    /// Print contract sizes after building
+    #[clap(
+        long,
+        help = "Print init code and deployed bytecode sizes of compiled contracts"
+    )]
+    pub sizes: bool,
+
+    /// Treat compiler warnings as errors
+    #[clap(
+        long,
+        help = "Fail the build if the compiler reports any warnings (ignored_error_codes still apply)"
+    )]
+    pub fail_on_warning: bool,
+}
+
+/// Bytecode sizes of a single compiled contract, reported by `pcl build --sizes --json`.
+#[derive(Debug, Serialize)]
+pub struct ContractSize {
+    /// Contract name
+    pub name: String,
+    /// Source file the contract was compiled from
+    pub source: PathBuf,
+    /// Size of the creation bytecode in bytes
+    pub init_code_size: usize,
+    /// Size of the runtime bytecode in bytes
+    pub deployed_size: usize,
+}
+
+/// Collects bytecode sizes for every contract that produced bytecode, sorted by name.
+pub fn contract_sizes(output: &ProjectCompileOutput) -> Vec<ContractSize> {
+    let mut sizes: Vec<ContractSize> = output
+        .artifact_ids()
+        .filter_map(|(id, artifact)| {
+            let init_code_size = artifact.get_bytecode_bytes().map_or(0, |b| b.len());
+            let deployed_size = artifact
+                .get_deployed_bytecode_bytes()
+                .map_or(0, |b| b.len());
+            // Interfaces and abstract contracts have no bytecode
+            (init_code_size > 0 || deployed_size > 0).then(|| {
+                ContractSize {
+                    name: id.name,
+                    source: id.source,
+                    init_code_size,
+                    deployed_size,
+                }
+            })
+        })
+        .collect();
+    sizes.sort_by(|a, b| a.name.cmp(&b.name));
+    sizes
 }
 
 impl BuildArgs {
     /// Builds the assertion contract and tests
     ///
+    /// # Arguments
+    ///
+    /// * `cli_args` - Global CLI arguments; `--verbose` un-silences the compiler
+    ///
     /// # Returns
     ///
     /// - `Ok(())`
     /// - `Err(PhoundryError)` if any step in the process fails
-    pub fn run(&self) -> Result<(), Box<PhoundryError>> {
+    pub fn run(&self, cli_args: &CliArgs) -> Result<(), Box<PhoundryError>> {
         let build_cmd = BuildOpts {
             project_paths: ProjectPathOpts {
                 root: self.root.clone(),
@@ -43,14 +129,91 @@ impl BuildArgs {
                 contracts: Some(PathBuf::from("assertions/src")),
                 ..Default::default()
             },
+            out: self.output_dir.clone(),
             ..Default::default()
         };
 
         foundry_cli::utils::load_dotenv();
 
-        compile(build_cmd)?;
+        // The spinner would interleave with compiler output in verbose mode and
+        // pollute machine-readable or redirected output otherwise.
+        let json_output = cli_args.json_output();
+        let show_spinner = !json_output
+            && !cli_args.verbose()
+            && !cli_args.quiet()
+            && std::io::stdout().is_terminal();
+        let spinner = show_spinner.then(Self::create_spinner);
+
+        let result = compile(
+            build_cmd.clone(),
+            CompileOptions {
+                verbose: cli_args.verbose(),
+                // The forge size table is human-readable only; JSON gets its own report
+                sizes: self.sizes && !json_output,
+                profile: self.profile_args.profile.clone(),
+                locked: self.profile_args.locked,
+            },
+        );
+
+        if let Some(spinner) = spinner {
+            match &result {
+                Ok(output) => {
+                    let count = output.artifacts().count();
+                    spinner.finish_with_message(format!(
+                        "✅ Compiled {count} contract{}",
+                        if count == 1 { "" } else { "s" }
+                    ));
+                }
+                Err(_) => spinner.finish_and_clear(),
+            }
+        }
+
+        let output = result?;
+        if json_output || self.fail_on_warning {
+            let config = load_config(&build_cmd, self.profile_args.profile.as_deref())?;
+            let warnings: Vec<_> = diagnostics(&output, &config)
+                .into_iter()
+                .filter(|diagnostic| diagnostic.severity == "warning")
+                .collect();
+            let failed = self.fail_on_warning && !warnings.is_empty();
+
+            if json_output {
+                let mut report = serde_json::json!({
+                    "status": if failed { "failed" } else { "success" },
+                    // Only warnings, matching what `--fail-on-warning` counts
+                    "warnings": warnings,
+                });
+                if self.sizes {
+                    report["contracts"] = serde_json::json!(contract_sizes(&output));
+                }
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report).map_err(|_| {
+                        PhoundryError::InvalidForgeOutput("Failed to serialize build report")
+                    })?
+                );
+            }
+            if failed {
+                return Err(Box::new(PhoundryError::WarningsFound(warnings)));
+            }
+        }
         Ok(())
     }
+
+    /// Creates a spinner shown while the project compiles
+    fn create_spinner() -> ProgressBar {
+        let spinner = ProgressBar::new_spinner();
+        spinner.set_style(
+            ProgressStyle::default_spinner()
+                .tick_chars("⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏")
+                .template("{spinner} {msg}")
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        spinner.enable_steady_tick(Duration::from_millis(80));
+        spinner.set_message("Compiling...");
+        pcl_common::spinner::track(&spinner);
+        spinner
+    }
 }
 
 #[cfg(test)]
@@ -142,7 +305,10 @@ contract InvalidContract {
 
     #[test]
     fn test_build_args_new() {
-        let args = BuildArgs { root: None };
+        let args = BuildArgs {
+            root: None,
+            ..Default::default()
+        };
 
         assert!(args.root.is_none());
     }
@@ -152,6 +318,7 @@ contract InvalidContract {
         let root_path = PathBuf::from("/test/path");
         let args = BuildArgs {
             root: Some(root_path.clone()),
+            ..Default::default()
         };
 
         assert_eq!(args.root, Some(root_path));
@@ -163,9 +330,10 @@ contract InvalidContract {
 
         let args = BuildArgs {
             root: Some(project_root),
+            ..Default::default()
         };
 
-        let result = args.run();
+        let result = args.run(&CliArgs::default());
 
         // Compilation should fail due to syntax errors
         assert!(
@@ -180,9 +348,10 @@ contract InvalidContract {
 
         let args = BuildArgs {
             root: Some(project_root),
+            ..Default::default()
         };
 
-        let result = args.run();
+        let result = args.run(&CliArgs::default());
 
         // Compilation should fail due to no source files
         assert!(
@@ -198,9 +367,10 @@ contract InvalidContract {
 
         let args = BuildArgs {
             root: Some(nonexistent_path),
+            ..Default::default()
         };
 
-        let result = args.run();
+        let result = args.run(&CliArgs::default());
 
         assert!(
             result.is_err(),
@@ -214,10 +384,55 @@ contract InvalidContract {
 
         let args = BuildArgs {
             root: Some(project_root),
+            ..Default::default()
         };
 
-        let result = args.run();
+        let result = args.run(&CliArgs::default());
 
         assert!(result.is_ok());
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_contract_sizes_for_valid_contract() {
+        let (_temp_dir, project_root) = setup_valid_test_project();
+
+        let build_opts = BuildOpts {
+            project_paths: ProjectPathOpts {
+                root: Some(project_root),
+                contracts: Some(PathBuf::from("assertions/src")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let output = compile(build_opts, CompileOptions::default()).unwrap();
+
+        let sizes = contract_sizes(&output);
+        let valid = sizes
+            .iter()
+            .find(|size| size.name == "ValidContract")
+            .expect("ValidContract should be reported");
+        assert!(valid.init_code_size > 0);
+        assert!(valid.deployed_size > 0);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_build_writes_artifacts_to_output_dir() {
+        let (temp_dir, project_root) = setup_valid_test_project();
+        let output_dir = temp_dir.path().join("custom_out");
+
+        let args = BuildArgs {
+            root: Some(project_root),
+            output_dir: Some(output_dir.clone()),
+            ..Default::default()
+        };
+
+        args.run(&CliArgs::default()).unwrap();
+
+        assert!(
+            output_dir
+                .join("ValidContract.sol")
+                .join("ValidContract.json")
+                .exists()
+        );
+    }
 }
@@ -2,12 +2,9 @@ use clap::{
     Parser,
     ValueHint,
 };
-use foundry_cli::{
-    opts::{
-        BuildOpts,
-        ProjectPathOpts,
-    },
-    utils::LoadConfig,
+use foundry_cli::opts::{
+    BuildOpts,
+    ProjectPathOpts,
 };
 use foundry_compilers::{
     ProjectCompileOutput,
@@ -15,7 +12,6 @@ use foundry_compilers::{
         Flattener,
         FlattenerError,
     },
-    info::ContractInfo,
     solc::SolcLanguage,
 };
 
@@ -24,12 +20,23 @@ use serde_json::Value;
 
 use foundry_compilers::artifacts::BytecodeHash;
 use foundry_config::find_project_root;
+use pcl_common::Assertion;
 use std::{
     collections::HashMap,
-    path::PathBuf,
+    path::{
+        Path,
+        PathBuf,
+    },
 };
 
-use crate::error::PhoundryError;
+use crate::{
+    compile::{
+        ProfileArgs,
+        load_config,
+    },
+    error::PhoundryError,
+    flatten_cache::FlattenCache,
+};
 
 /// Output from building and flattening a Solidity contract.
 /// Contains the compiler version used and the flattened source code.
@@ -130,9 +137,48 @@ pub struct BuildAndFlattenArgs {
     )]
     pub root: Option<PathBuf>,
 
-    /// Name of the assertion contract to build and flatten
-    #[clap(help = "Name of the assertion contract to build and flatten")]
+    /// Directory to write build artifacts to
+    #[clap(
+        long,
+        value_hint = ValueHint::DirPath,
+        help = "Directory to write build artifacts to (defaults to the foundry `out` setting)"
+    )]
+    pub output_dir: Option<PathBuf>,
+
+    /// Name of the assertion contract to build and flatten, optionally as `path:Name`
+    #[clap(
+        help = "Name of the assertion contract to build and flatten. Use `path:Name` when several files define the same name"
+    )]
     pub assertion_contract: String,
+
+    /// Print compiler output while building
+    #[clap(long, help = "Print compiler output while building")]
+    pub verbose: bool,
+
+    /// Skip the flatten cache and always reflatten the contract
+    #[clap(
+        long,
+        help = "Always reflatten instead of reusing cached flattened sources"
+    )]
+    pub no_cache: bool,
+
+    /// Prepend a comment listing the original source files and remappings
+    #[clap(
+        long,
+        help = "Prepend a comment listing the original source files and remappings to the flattened source"
+    )]
+    pub annotate_sources: bool,
+
+    /// Assertion file extensions, most preferred first
+    #[clap(
+        long = "assertion-extension",
+        value_delimiter = ',',
+        help = "Assertion file extensions used to break ties between same-named contracts (defaults to .a.sol,.sol)"
+    )]
+    pub assertion_extensions: Vec<String>,
+
+    #[clap(flatten)]
+    pub profile_args: ProfileArgs,
 }
 
 impl BuildAndFlattenArgs {
@@ -146,12 +192,44 @@ impl BuildAndFlattenArgs {
         foundry_cli::utils::load_dotenv();
 
         let build = self.build()?;
-        let info = ContractInfo::new(&self.assertion_contract);
 
-        // Find the contract artifact
-        let artifact = build
-            .find_contract(info)
-            .ok_or_else(|| PhoundryError::ContractNotFound(self.assertion_contract.clone()))?;
+        // Find the contract artifact. `path:Name` pins the source file; for a bare
+        // name, a file named after the assertion (`Name.a.sol` or `Name.sol`) wins
+        // over same-named dependencies, and any other collision is an error.
+        let (source_path, contract_name) = split_qualified_name(&self.assertion_contract);
+        let mut candidates: Vec<_> = build
+            .artifact_ids()
+            .filter(|(id, _)| id.name == contract_name)
+            .filter(|(id, _)| source_path.is_none_or(|path| id.source.ends_with(path)))
+            .collect();
+        candidates.sort_by(|(a, _), (b, _)| a.source.cmp(&b.source));
+        candidates.dedup_by(|(a, _), (b, _)| a.source == b.source);
+        if candidates.len() > 1 {
+            let file_names = Assertion::new(None, contract_name.to_string())
+                .with_extensions(&self.assertion_extensions)
+                .get_paths();
+            let named = candidates
+                .iter()
+                .filter(|(id, _)| is_named_after(&id.source, &file_names))
+                .count();
+            if named == 1 {
+                candidates.retain(|(id, _)| is_named_after(&id.source, &file_names));
+            } else {
+                return Err(Box::new(PhoundryError::AmbiguousContract(
+                    contract_name.to_string(),
+                    candidates.into_iter().map(|(id, _)| id.source).collect(),
+                )));
+            }
+        }
+        let Some((artifact_id, artifact)) = candidates.into_iter().next() else {
+            let suggestions =
+                closest_names(contract_name, build.artifact_ids().map(|(id, _)| id.name));
+            return Err(Box::new(PhoundryError::ContractNotFound(
+                self.assertion_contract.clone(),
+                suggestions,
+            )));
+        };
+        tracing::debug!(source = %artifact_id.source.display(), "resolved assertion artifact");
 
         let abi = artifact.abi.clone().ok_or_else(|| {
             PhoundryError::InvalidForgeOutput("Failed to parse ABI from artifact")
@@ -176,27 +254,55 @@ impl BuildAndFlattenArgs {
             .compilation_target
             .iter()
             .find_map(|(path, name)| {
-                if name == &self.assertion_contract {
+                if name == contract_name {
                     Some(path)
                 } else {
                     None
                 }
             })
-            .ok_or_else(|| PhoundryError::ContractNotFound(self.assertion_contract.clone()))?;
+            .ok_or_else(|| {
+                PhoundryError::ContractNotFound(self.assertion_contract.clone(), Vec::new())
+            })?;
 
         // Determine the full path to the contract
-        let path = match &self.root {
-            Some(root) => root.join(rel_source_path),
+        let root = match &self.root {
+            Some(root) => root.clone(),
             None => {
                 find_project_root(None)
                     .map_err(|_| PhoundryError::DirectoryNotFound(PathBuf::from(".")))?
-                    .join(rel_source_path)
             }
         };
-
-        // Flatten the contract
-        let flattened = self.flatten(&path)?;
-        let output = BuildAndFlatOutput::new(
+        let path = path_within_root(&root, Path::new(rel_source_path))?;
+
+        // Flatten the contract, reusing a cached result while its sources are unchanged
+        let sources = serde_json::to_value(&metadata)
+            .map_err(|_| {
+                PhoundryError::InvalidForgeOutput("Failed to serialize contract metadata")
+            })?
+            .get("sources")
+            .cloned()
+            .unwrap_or_default();
+        let cache_key = FlattenCache::key(&solc_version, rel_source_path, &sources);
+        let cache = if self.no_cache {
+            None
+        } else {
+            FlattenCache::open()
+        };
+        let flattened = match cache.as_ref().and_then(|cache| cache.get(&cache_key)) {
+            Some(flattened) => {
+                tracing::debug!(contract = %self.assertion_contract, "flatten cache hit");
+                flattened
+            }
+            None => {
+                tracing::debug!(contract = %self.assertion_contract, path = %path.display(), "flattening contract");
+                let flattened = self.flatten(&path)?;
+                if let Some(cache) = &cache {
+                    cache.put(&cache_key, &flattened);
+                }
+                flattened
+            }
+        };
+        let mut output = BuildAndFlatOutput::new(
             solc_version,
             flattened,
             abi,
@@ -233,6 +339,11 @@ impl BuildAndFlattenArgs {
             flatten_libraries(&settings),
             rel_source_path.clone(),
         );
+        // Annotated after the cache lookup so the cache only holds plain flattened sources
+        if self.annotate_sources {
+            output.flattened_source =
+                annotate_sources(&output.flattened_source, &sources, &output.remappings);
+        }
         output.validate()?;
         Ok(output)
     }
@@ -247,10 +358,19 @@ impl BuildAndFlattenArgs {
                 contracts: Some(PathBuf::from("assertions/src")),
                 ..Default::default()
             },
+            out: self.output_dir.clone(),
             ..Default::default()
         };
 
-        crate::compile::compile(build_opts)
+        crate::compile::compile(
+            build_opts,
+            crate::compile::CompileOptions {
+                verbose: self.verbose,
+                profile: self.profile_args.profile.clone(),
+                locked: self.profile_args.locked,
+                ..Default::default()
+            },
+        )
     }
 
     /// Flattens the contract source code.
@@ -263,7 +383,7 @@ impl BuildAndFlattenArgs {
             ..Default::default()
         };
 
-        let config = build.load_config()?;
+        let config = load_config(&build, self.profile_args.profile.as_deref())?;
         let project = config
             .ephemeral_project()
             .map_err(|e| Box::new(PhoundryError::SolcError(e)))?;
@@ -290,6 +410,98 @@ impl BuildAndFlattenArgs {
     }
 }
 
+/// Joins `relative` onto `root` and canonicalizes it, rejecting paths that
+/// resolve outside the project, e.g. through `..` or an absolute path.
+fn path_within_root(root: &Path, relative: &Path) -> Result<PathBuf, Box<PhoundryError>> {
+    let root = std::fs::canonicalize(root).map_err(PhoundryError::CanonicalizePathError)?;
+    let path =
+        std::fs::canonicalize(root.join(relative)).map_err(PhoundryError::CanonicalizePathError)?;
+    if !path.starts_with(&root) {
+        return Err(Box::new(PhoundryError::InvalidPath(path)));
+    }
+    Ok(path)
+}
+
+/// Prepends a comment block naming the files that were flattened into `flattened`
+/// and the remappings used to resolve their imports.
+fn annotate_sources(flattened: &str, sources: &Value, remappings: &[String]) -> String {
+    let mut files: Vec<&str> = sources
+        .as_object()
+        .map(|sources| sources.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    files.sort_unstable();
+
+    let mut header = String::from("// Flattened by pcl from:\n");
+    for file in files {
+        header.push_str(&format!("//   {file}\n"));
+    }
+    if !remappings.is_empty() {
+        header.push_str("// Remappings:\n");
+        for remapping in remappings {
+            header.push_str(&format!("//   {remapping}\n"));
+        }
+    }
+    header.push('\n');
+    header + flattened
+}
+
+/// Splits `path:Name` into its source path and contract name.
+fn split_qualified_name(qualified: &str) -> (Option<&Path>, &str) {
+    match qualified.rsplit_once(':') {
+        Some((path, name)) => (Some(Path::new(path)), name),
+        None => (None, qualified),
+    }
+}
+
+/// Maximum number of suggestions listed when a contract is not found
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Returns up to [`MAX_SUGGESTIONS`] names close to `target`, nearest first.
+///
+/// Names are compared case-insensitively and only kept when the edit distance
+/// is small relative to the target's length, so unrelated contracts are not
+/// suggested.
+fn closest_names(target: &str, names: impl IntoIterator<Item = String>) -> Vec<String> {
+    let target_lower = target.to_lowercase();
+    let max_distance = (target.chars().count() / 3).max(2);
+    let mut scored: Vec<(usize, String)> = names
+        .into_iter()
+        .filter(|name| name != target)
+        .map(|name| (edit_distance(&target_lower, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, name)| name)
+        .collect()
+}
+
+/// Levenshtein distance between two strings, counted in chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Whether `source` has one of the given file names.
+fn is_named_after(source: &Path, file_names: &[String]) -> bool {
+    source
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| file_names.iter().any(|file_name| file_name == name))
+}
+
 fn extract_bytecode<T: serde::Serialize>(bytecode: &T) -> Option<String> {
     let value = serde_json::to_value(bytecode).ok()?;
     value
@@ -354,11 +566,166 @@ contract TestContract {
         (temp_dir, project_root)
     }
 
+    #[test]
+    fn test_is_named_after() {
+        let file_names = Assertion::new(None, "MyAssertion".to_string()).get_paths();
+
+        assert!(is_named_after(
+            Path::new("assertions/src/MyAssertion.a.sol"),
+            &file_names
+        ));
+        assert!(is_named_after(
+            Path::new("/abs/assertions/src/MyAssertion.sol"),
+            &file_names
+        ));
+        assert!(!is_named_after(
+            Path::new("assertions/src/Other.a.sol"),
+            &file_names
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_build_and_flatten_finds_a_sol_assertion() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_root = temp_dir.path().join("test_project");
+        let assertions_dir = project_root.join("assertions").join("src");
+        fs::create_dir_all(&assertions_dir).unwrap();
+        fs::write(
+            assertions_dir.join("MyAssertion.a.sol"),
+            r"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.0;
+
+contract MyAssertion {
+    function check() public pure returns (bool) {
+        return true;
+    }
+}",
+        )
+        .unwrap();
+
+        let args = BuildAndFlattenArgs {
+            root: Some(project_root),
+            assertion_contract: "MyAssertion".to_string(),
+            no_cache: true,
+            ..Default::default()
+        };
+
+        let output = args.run().unwrap();
+        assert!(output.compilation_target.ends_with("MyAssertion.a.sol"));
+        assert!(output.flattened_source.contains("contract MyAssertion"));
+    }
+
+    #[test]
+    fn test_path_within_root_rejects_escaping_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join("project");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("src").join("A.sol"), "").unwrap();
+        let outside = temp_dir.path().join("Outside.sol");
+        fs::write(&outside, "").unwrap();
+
+        let path = path_within_root(&root, Path::new("src/A.sol")).unwrap();
+        assert!(path.ends_with("src/A.sol"));
+
+        for escaping in [Path::new("../Outside.sol"), outside.as_path()] {
+            assert!(matches!(
+                *path_within_root(&root, escaping).unwrap_err(),
+                PhoundryError::InvalidPath(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_annotate_sources() {
+        let sources = serde_json::json!({
+            "src/B.sol": { "keccak256": "0x02" },
+            "assertions/src/A.a.sol": { "keccak256": "0x01" },
+        });
+        let annotated = annotate_sources(
+            "pragma solidity ^0.8.0;\n",
+            &sources,
+            &["forge-std/=lib/forge-std/src/".to_string()],
+        );
+        assert_eq!(
+            annotated,
+            "// Flattened by pcl from:\n//   assertions/src/A.a.sol\n//   src/B.sol\n// Remappings:\n//   forge-std/=lib/forge-std/src/\n\npragma solidity ^0.8.0;\n"
+        );
+        assert!(!annotate_sources("", &sources, &[]).contains("Remappings"));
+    }
+
+    #[test]
+    fn test_split_qualified_name() {
+        assert_eq!(split_qualified_name("MyAssertion"), (None, "MyAssertion"));
+        assert_eq!(
+            split_qualified_name("assertions/src/V2.a.sol:MyAssertion"),
+            (Some(Path::new("assertions/src/V2.a.sol")), "MyAssertion")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_build_and_flatten_disambiguates_same_named_contracts() {
+        let temp_dir = TempDir::new().unwrap();
+        let project_root = temp_dir.path().join("test_project");
+        let assertions_dir = project_root.join("assertions").join("src");
+        fs::create_dir_all(&assertions_dir).unwrap();
+        for (file, result) in [("V1.a.sol", "true"), ("V2.a.sol", "false")] {
+            fs::write(
+                assertions_dir.join(file),
+                format!(
+                    "// SPDX-License-Identifier: MIT\npragma solidity ^0.8.0;\n\ncontract Shared {{\n    function check() public pure returns (bool) {{\n        return {result};\n    }}\n}}"
+                ),
+            )
+            .unwrap();
+        }
+
+        let args = |assertion_contract: &str| {
+            BuildAndFlattenArgs {
+                root: Some(project_root.clone()),
+                assertion_contract: assertion_contract.to_string(),
+                no_cache: true,
+                ..Default::default()
+            }
+        };
+
+        match *args("Shared").run().unwrap_err() {
+            PhoundryError::AmbiguousContract(name, sources) => {
+                assert_eq!(name, "Shared");
+                assert_eq!(sources.len(), 2);
+            }
+            other => panic!("unexpected error: {other}"),
+        }
+
+        let output = args("assertions/src/V2.a.sol:Shared").run().unwrap();
+        assert!(output.compilation_target.ends_with("V2.a.sol"));
+        assert!(output.flattened_source.contains("return false"));
+    }
+
+    #[test]
+    fn test_closest_names() {
+        let names = [
+            "OwnableAssertion",
+            "OwnableAssertion",
+            "OwnerAssertion",
+            "Counter",
+            "MockProtocol",
+        ]
+        .map(String::from);
+
+        assert_eq!(
+            closest_names("OwnablAssertion", names.clone()),
+            vec!["OwnableAssertion", "OwnerAssertion"]
+        );
+        assert_eq!(closest_names("counter", names.clone()), vec!["Counter"]);
+        assert!(closest_names("Unrelated", names).is_empty());
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
     #[test]
     fn test_build_and_flatten_args_new() {
         let args = BuildAndFlattenArgs {
             root: None,
             assertion_contract: "TestContract".to_string(),
+            ..Default::default()
         };
 
         assert_eq!(args.assertion_contract, "TestContract");
@@ -412,6 +779,7 @@ contract TestContract {
         let args = BuildAndFlattenArgs {
             root: Some(project_root),
             assertion_contract: "TestContract".to_string(),
+            ..Default::default()
         };
 
         let result = args.run();
@@ -4,21 +4,238 @@ use foundry_cli::{
     utils::LoadConfig,
 };
 use foundry_common::compile::ProjectCompiler;
-use foundry_compilers::ProjectCompileOutput;
+use foundry_compilers::{
+    ProjectCompileOutput,
+    compilers::{
+        CompilationError,
+        multi::MultiCompilerError,
+    },
+};
+use foundry_config::Config;
+use serde::Serialize;
+use std::path::{
+    Path,
+    PathBuf,
+};
 
 use crate::error::PhoundryError;
 
+/// Files pinning dependency versions that a `--locked` build must not modify
+const PINNED_FILES: &[&str] = &["foundry.lock", ".gitmodules", "remappings.txt"];
+
+/// Build options for [`compile`].
+#[derive(Debug, Default, Clone)]
+pub struct CompileOptions {
+    /// Print compiler progress, warnings and diagnostics instead of running quietly
+    pub verbose: bool,
+    /// Print a contract size table once compilation finishes
+    pub sizes: bool,
+    /// Foundry profile to build with instead of the default one
+    pub profile: Option<String>,
+    /// Build offline and fail if pinned dependency files would change
+    pub locked: bool,
+}
+
+/// Foundry profile and lockfile flags shared by every command that compiles.
+#[derive(Debug, Default, Clone, clap::Args)]
+pub struct ProfileArgs {
+    /// Foundry profile to build with
+    #[clap(
+        long,
+        help = "Foundry profile to build with (defaults to FOUNDRY_PROFILE or `default`)"
+    )]
+    pub profile: Option<String>,
+
+    /// Build offline and refuse to modify pinned dependency files
+    #[clap(
+        long,
+        visible_alias = "frozen",
+        help = "Build offline and fail if foundry.lock, .gitmodules or remappings.txt would change"
+    )]
+    pub locked: bool,
+}
+
+/// A non-fatal compiler diagnostic, reported by `pcl build --json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// Compiler severity, e.g. `warning` or `info`
+    pub severity: String,
+    /// Compiler message without source excerpt
+    pub message: String,
+    /// Source file the diagnostic points at, relative to the project root
+    pub file: Option<PathBuf>,
+    /// 1-based line number in `file`
+    pub line: Option<usize>,
+    /// Solidity error code, as used by `ignored_error_codes` in foundry.toml
+    pub code: Option<u64>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => write!(f, "{}:{line}: ", file.display())?,
+            (Some(file), None) => write!(f, "{}: ", file.display())?,
+            _ => {}
+        }
+        write!(f, "{}", self.message)?;
+        if let Some(code) = self.code {
+            write!(f, " ({code})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects warnings and infos from the compiler output.
+///
+/// Codes listed in the config's `ignored_error_codes` are skipped, matching
+/// what forge itself reports.
+pub fn diagnostics(output: &ProjectCompileOutput, config: &Config) -> Vec<Diagnostic> {
+    let ignored: Vec<u64> = config
+        .ignored_error_codes
+        .iter()
+        .copied()
+        .map(Into::into)
+        .collect();
+    output
+        .output()
+        .errors
+        .iter()
+        .filter(|error| !error.is_error())
+        .filter(|error| {
+            error
+                .error_code()
+                .is_none_or(|code| !ignored.contains(&code))
+        })
+        .map(|error| {
+            let message = match error {
+                MultiCompilerError::Solc(error) => error.message.clone(),
+                other => other.to_string(),
+            };
+            let location = error.source_location();
+            let file = location.as_ref().map(|loc| PathBuf::from(&loc.file));
+            let line = location.as_ref().and_then(|loc| {
+                let contents = std::fs::read_to_string(config.root.join(&loc.file)).ok()?;
+                line_of_offset(&contents, usize::try_from(loc.start).ok()?)
+            });
+            Diagnostic {
+                severity: error.severity().to_string().to_lowercase(),
+                message,
+                file,
+                line,
+                code: error.error_code(),
+            }
+        })
+        .collect()
+}
+
+/// Converts a byte offset into a 1-based line number.
+fn line_of_offset(contents: &str, offset: usize) -> Option<usize> {
+    let prefix = contents.get(..offset)?;
+    Some(prefix.matches('\n').count() + 1)
+}
+
+/// Contents of the pinned dependency files in a project root.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct PinnedFiles(Vec<(PathBuf, Option<Vec<u8>>)>);
+
+impl PinnedFiles {
+    /// Reads the current contents of every pinned file under `root`.
+    pub(crate) fn snapshot(root: &Path) -> Self {
+        Self(
+            PINNED_FILES
+                .iter()
+                .map(|name| {
+                    let path = root.join(name);
+                    let contents = std::fs::read(&path).ok();
+                    (path, contents)
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns an error naming the first pinned file that no longer matches.
+    ///
+    /// Every changed file is put back as it was snapshotted first, so a failed
+    /// locked build leaves the project untouched.
+    pub(crate) fn ensure_unchanged(&self) -> Result<(), Box<PhoundryError>> {
+        let mut changed = None;
+        for (path, contents) in &self.0 {
+            if std::fs::read(path).ok() == *contents {
+                continue;
+            }
+            let restored = match contents {
+                Some(contents) => std::fs::write(path, contents),
+                None => std::fs::remove_file(path),
+            };
+            if let Err(e) = restored {
+                tracing::warn!(path = %path.display(), error = %e, "failed to restore pinned file");
+            }
+            changed.get_or_insert_with(|| path.clone());
+        }
+        match changed {
+            Some(path) => Err(Box::new(PhoundryError::LockfileChanged(path))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Loads the foundry config for `args` with `profile` selected.
+///
+/// Without a profile foundry falls back to `FOUNDRY_PROFILE` or `default`.
+/// The selection goes through the figment rather than the environment, so it
+/// only affects this load.
+pub(crate) fn load_config<T: LoadConfig>(
+    args: &T,
+    profile: Option<&str>,
+) -> Result<Config, Box<PhoundryError>> {
+    let Some(profile) = profile else {
+        return Ok(args.load_config()?);
+    };
+    // Checked before selecting, as foundry lists the selected profile as declared
+    ensure_profile_exists(&args.load_config()?, Some(profile))?;
+    Ok(Config::from_provider(args.figment().select(profile))?.sanitized())
+}
+
+/// Checks that the selected profile is declared in the resolved foundry config.
+pub(crate) fn ensure_profile_exists(
+    config: &Config,
+    profile: Option<&str>,
+) -> Result<(), Box<PhoundryError>> {
+    match profile {
+        Some(profile) if !config.profiles.iter().any(|p| p.as_str() == profile) => {
+            Err(Box::new(PhoundryError::InvalidFoundryProfile(
+                profile.to_string(),
+                config.root.join(Config::FILE_NAME),
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Compiles the project and returns the compilation output.
-pub fn compile(build_opts: BuildOpts) -> Result<ProjectCompileOutput, Box<PhoundryError>> {
+pub fn compile(
+    build_opts: BuildOpts,
+    options: CompileOptions,
+) -> Result<ProjectCompileOutput, Box<PhoundryError>> {
     let build_cmd = BuildArgs {
         build: build_opts,
+        sizes: options.sizes,
         ..Default::default()
     };
 
-    let config = build_cmd.load_config()?;
+    let mut config = load_config(&build_cmd, options.profile.as_deref())?;
+    // A locked build never reaches out to install or update anything
+    config.offline |= options.locked;
+    let pinned = options.locked.then(|| PinnedFiles::snapshot(&config.root));
 
     let project = config.project().map_err(PhoundryError::SolcError)?;
     let contracts = project.sources_path();
+    tracing::debug!(
+        root = %config.root.display(),
+        sources = %contracts.display(),
+        profile = %config.profile,
+        "compiling project"
+    );
 
     match std::fs::read_dir(contracts) {
         Ok(mut files) => {
@@ -40,10 +257,129 @@ pub fn compile(build_opts: BuildOpts) -> Result<ProjectCompileOutput, Box<Phound
         .print_sizes(build_cmd.sizes)
         .ignore_eip_3860(build_cmd.ignore_eip_3860)
         .bail(true)
-        .quiet(true);
+        .quiet(!options.verbose);
 
-    let res = compiler
-        .compile(&project)
-        .map_err(PhoundryError::CompilationError)?;
+    let res = compiler.compile(&project);
+    // Checked even when compilation failed, since dependencies may have been touched
+    if let Some(pinned) = pinned {
+        pinned.ensure_unchanged()?;
+    }
+    let res = res.map_err(PhoundryError::CompilationError)?;
+    tracing::debug!(artifacts = res.artifacts().count(), "compilation finished");
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnostic_display() {
+        let diagnostic = Diagnostic {
+            severity: "warning".to_string(),
+            message: "Unused local variable.".to_string(),
+            file: Some(PathBuf::from("assertions/src/A.a.sol")),
+            line: Some(7),
+            code: Some(2072),
+        };
+        assert_eq!(
+            diagnostic.to_string(),
+            "assertions/src/A.a.sol:7: Unused local variable. (2072)"
+        );
+        let diagnostic = Diagnostic {
+            file: None,
+            line: None,
+            code: None,
+            ..diagnostic
+        };
+        assert_eq!(diagnostic.to_string(), "Unused local variable.");
+    }
+
+    #[test]
+    fn test_line_of_offset() {
+        let contents = "pragma solidity ^0.8.0;\n\ncontract A {}\n";
+        assert_eq!(line_of_offset(contents, 0), Some(1));
+        assert_eq!(line_of_offset(contents, 25), Some(3));
+        assert_eq!(line_of_offset(contents, 1000), None);
+    }
+
+    #[test]
+    fn test_pinned_files_detect_changes() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("foundry.lock"), "{}").unwrap();
+
+        let pinned = PinnedFiles::snapshot(temp_dir.path());
+        assert!(pinned.ensure_unchanged().is_ok());
+
+        std::fs::write(temp_dir.path().join("remappings.txt"), "forge-std/=lib/").unwrap();
+        let err = pinned.ensure_unchanged().unwrap_err();
+        assert!(matches!(
+            *err,
+            PhoundryError::LockfileChanged(ref path) if path.ends_with("remappings.txt")
+        ));
+    }
+
+    #[test]
+    fn test_pinned_files_are_restored_on_change() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let lock = temp_dir.path().join("foundry.lock");
+        std::fs::write(&lock, "{}").unwrap();
+        let pinned = PinnedFiles::snapshot(temp_dir.path());
+
+        std::fs::write(&lock, r#"{"lib/forge-std":{}}"#).unwrap();
+        std::fs::write(temp_dir.path().join("remappings.txt"), "forge-std/=lib/").unwrap();
+        let err = pinned.ensure_unchanged().unwrap_err();
+
+        assert!(matches!(
+            *err,
+            PhoundryError::LockfileChanged(ref path) if path.ends_with("foundry.lock")
+        ));
+        assert_eq!(std::fs::read_to_string(&lock).unwrap(), "{}");
+        assert!(!temp_dir.path().join("remappings.txt").exists());
+        assert_eq!(PinnedFiles::snapshot(temp_dir.path()), pinned);
+    }
+
+    #[test]
+    fn test_load_config_selects_profile() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join(Config::FILE_NAME),
+            "[profile.default]\nsrc = \"src\"\n\n[profile.assertions]\nsrc = \"assertions/src\"\n",
+        )
+        .unwrap();
+        let build = BuildOpts {
+            project_paths: foundry_cli::opts::ProjectPathOpts {
+                root: Some(temp_dir.path().to_path_buf()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let config = load_config(&build, Some("assertions")).unwrap();
+        assert_eq!(config.profile.as_str(), "assertions");
+        assert_eq!(config.src, PathBuf::from("assertions/src"));
+
+        let config = load_config(&build, None).unwrap();
+        assert_eq!(config.src, PathBuf::from("src"));
+
+        let err = load_config(&build, Some("missing")).unwrap_err();
+        assert!(matches!(
+            *err,
+            PhoundryError::InvalidFoundryProfile(ref profile, _) if profile == "missing"
+        ));
+    }
+
+    #[test]
+    fn test_ensure_profile_exists() {
+        let config = Config::default();
+
+        assert!(ensure_profile_exists(&config, None).is_ok());
+        assert!(ensure_profile_exists(&config, Some("default")).is_ok());
+
+        let err = ensure_profile_exists(&config, Some("assertions")).unwrap_err();
+        assert!(matches!(
+            *err,
+            PhoundryError::InvalidFoundryProfile(ref profile, _) if profile == "assertions"
+        ));
+    }
+}
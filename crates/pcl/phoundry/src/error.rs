@@ -28,8 +28,13 @@ pub enum PhoundryError {
     InvalidFoundryProfile(String, PathBuf),
     #[error("Phoundry failed to extract the config: {0}")]
     FoundryConfigError(#[source] foundry_config::error::ExtractConfigError),
-    #[error("Contract {0} was not found in the build output")]
-    ContractNotFound(String),
+    #[error("Contract {0} was not found in the build output{}", did_you_mean(.1))]
+    ContractNotFound(String, Vec<String>),
+    #[error(
+        "Contract {0} is defined in several files: {}. Use `<path>:{0}` to pick one",
+        .1.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(", ")
+    )]
+    AmbiguousContract(String, Vec<PathBuf>),
     #[error("Invalid path: {0:?}")]
     InvalidPath(PathBuf),
     #[error("Directory not found: {0:?}")]
@@ -42,10 +47,34 @@ pub enum PhoundryError {
     FlattenerError(#[source] FlattenerError),
     #[error("No source files found in specified build paths.")]
     NoSourceFilesFound,
+    #[error(
+        "No Solidity files found in test directory {0:?}. Add test contracts there or set `test` in foundry.toml."
+    )]
+    NoTestFilesFound(PathBuf),
+    #[error("{0} test{} failed", if *.0 == 1 { "" } else { "s" })]
+    TestsFailed(usize),
+    #[error("Build would modify pinned dependency file {0:?}; run without --locked to update it")]
+    LockfileChanged(PathBuf),
+    #[error(
+        "Build produced {} warning{} and --fail-on-warning is set:\n{}",
+        .0.len(),
+        if .0.len() == 1 { "" } else { "s" },
+        .0.iter().map(|warning| format!("  {warning}")).collect::<Vec<_>>().join("\n")
+    )]
+    WarningsFound(Vec<crate::compile::Diagnostic>),
     #[error("Compilation failed:\n{0}")]
     CompilationError(eyre::Report),
 }
 
+/// Formats near-match suggestions appended to "not found" errors.
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(". Did you mean: {}?", suggestions.join(", "))
+    }
+}
+
 impl From<ExtractConfigError> for Box<PhoundryError> {
     fn from(error: ExtractConfigError) -> Self {
         Box::new(PhoundryError::FoundryConfigError(error))
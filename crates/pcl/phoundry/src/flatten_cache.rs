@@ -0,0 +1,194 @@
+//! Content-addressed cache for flattened assertion sources.
+//!
+//! Entries are keyed on the compiler version, the contract's source path and the
+//! metadata of every source file it depends on. The metadata carries a keccak256
+//! hash per file, so any content change produces a new key and stale entries are
+//! simply never read again.
+
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{
+    Digest,
+    Sha256,
+};
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+/// Directory name under the user cache directory
+const CACHE_DIR_NAME: &str = "pcl";
+/// Subdirectory holding flattened sources
+const FLATTEN_DIR_NAME: &str = "flatten";
+
+/// Returns the root cache directory used by pcl, if the platform has one.
+///
+/// This is `$XDG_CACHE_HOME/pcl` (usually `~/.cache/pcl`) on Linux and the
+/// platform equivalent elsewhere.
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join(CACHE_DIR_NAME))
+}
+
+/// Returns the directory holding cached flattened sources, if the platform has
+/// a cache directory.
+pub fn flatten_dir() -> Option<PathBuf> {
+    cache_dir().map(|dir| dir.join(FLATTEN_DIR_NAME))
+}
+
+/// Number and total size of the entries in a cache directory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub entries: u64,
+    pub bytes: u64,
+}
+
+/// Counts the cached flattened sources in `dir`. A missing directory is empty.
+pub fn stats(dir: &Path) -> std::io::Result<CacheStats> {
+    let mut stats = CacheStats::default();
+    for path in entries(dir)? {
+        stats.entries += 1;
+        stats.bytes += std::fs::metadata(&path)?.len();
+    }
+    Ok(stats)
+}
+
+/// Removes every cached flattened source in `dir` and returns what was removed.
+///
+/// Only cache entries are deleted, so an unexpected file in the directory is
+/// left alone.
+pub fn clear(dir: &Path) -> std::io::Result<CacheStats> {
+    let mut removed = CacheStats::default();
+    for path in entries(dir)? {
+        let len = std::fs::metadata(&path)?.len();
+        std::fs::remove_file(&path)?;
+        removed.entries += 1;
+        removed.bytes += len;
+    }
+    Ok(removed)
+}
+
+/// Paths of the cache entries in `dir`.
+fn entries(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let read_dir = match std::fs::read_dir(dir) {
+        Ok(read_dir) => read_dir,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut paths = Vec::new();
+    for entry in read_dir {
+        let path = entry?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "sol") {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// On-disk store of flattened sources.
+#[derive(Debug)]
+pub(crate) struct FlattenCache {
+    dir: PathBuf,
+}
+
+impl FlattenCache {
+    /// Opens the cache in the default location, or `None` if no cache directory
+    /// can be resolved.
+    pub(crate) fn open() -> Option<Self> {
+        flatten_dir().map(|dir| Self { dir })
+    }
+
+    /// Computes the cache key for a contract.
+    ///
+    /// `sources` is the serialized `sources` section of the contract metadata.
+    pub(crate) fn key(compiler_version: &str, source_path: &str, sources: &Value) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(compiler_version.as_bytes());
+        hasher.update([0]);
+        hasher.update(source_path.as_bytes());
+        hasher.update([0]);
+        hasher.update(sources.to_string().as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Returns the cached flattened source for `key`, if present.
+    pub(crate) fn get(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.entry_path(key)).ok()
+    }
+
+    /// Stores a flattened source. The cache is best-effort, so write failures
+    /// are ignored.
+    ///
+    /// The entry is written to a temporary file and renamed into place, so a
+    /// concurrent `get` never sees a partially written source.
+    pub(crate) fn put(&self, key: &str, flattened: &str) {
+        if std::fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+        let temp_file = self.dir.join(format!("{key}.{}.tmp", std::process::id()));
+        if std::fs::write(&temp_file, flattened).is_err()
+            || std::fs::rename(&temp_file, self.entry_path(key)).is_err()
+        {
+            let _ = std::fs::remove_file(&temp_file);
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{key}.sol"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_key_changes_with_source_content() {
+        let sources = json!({ "src/A.sol": { "keccak256": "0x01" } });
+        let changed = json!({ "src/A.sol": { "keccak256": "0x02" } });
+
+        let key = FlattenCache::key("v0.8.28", "src/A.sol", &sources);
+        assert_eq!(key, FlattenCache::key("v0.8.28", "src/A.sol", &sources));
+        assert_ne!(key, FlattenCache::key("v0.8.28", "src/A.sol", &changed));
+        assert_ne!(key, FlattenCache::key("v0.8.29", "src/A.sol", &sources));
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let temp_dir = TempDir::new().unwrap();
+        let cache = FlattenCache {
+            dir: temp_dir.path().join(FLATTEN_DIR_NAME),
+        };
+
+        assert!(cache.get("missing").is_none());
+        cache.put("key", "contract A {}");
+        assert_eq!(cache.get("key").as_deref(), Some("contract A {}"));
+
+        // Overwriting replaces the entry and leaves no temporary files behind
+        cache.put("key", "contract B {}");
+        assert_eq!(cache.get("key").as_deref(), Some("contract B {}"));
+        assert_eq!(std::fs::read_dir(&cache.dir).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_stats_and_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let dir = temp_dir.path().join(FLATTEN_DIR_NAME);
+        assert_eq!(stats(&dir).unwrap(), CacheStats::default());
+
+        let cache = FlattenCache { dir: dir.clone() };
+        cache.put("a", "contract A {}");
+        cache.put("b", "contract B {}");
+        std::fs::write(dir.join("notes.txt"), "keep me").unwrap();
+
+        let expected = CacheStats {
+            entries: 2,
+            bytes: 26,
+        };
+        assert_eq!(stats(&dir).unwrap(), expected);
+        assert_eq!(clear(&dir).unwrap(), expected);
+        assert_eq!(stats(&dir).unwrap(), CacheStats::default());
+        assert!(dir.join("notes.txt").exists());
+    }
+}
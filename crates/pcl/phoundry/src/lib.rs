@@ -4,4 +4,9 @@ pub mod build;
 pub mod build_and_flatten;
 pub mod compile;
 pub mod error;
+pub mod flatten_cache;
+pub mod lint;
 pub mod phorge_test;
+
+/// Version of the foundry crates pcl is built against, e.g. `1.5.1-nightly`
+pub const FOUNDRY_VERSION: &str = foundry_common::version::SHORT_VERSION;
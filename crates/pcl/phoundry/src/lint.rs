@@ -0,0 +1,291 @@
+use alloy_json_abi::JsonAbi;
+use clap::{
+    Parser,
+    ValueHint,
+};
+use foundry_cli::opts::{
+    BuildOpts,
+    ProjectPathOpts,
+};
+use foundry_compilers::{
+    Artifact,
+    ProjectCompileOutput,
+};
+use foundry_config::find_project_root;
+use pcl_common::args::CliArgs;
+use serde::Serialize;
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use crate::{
+    compile::{
+        CompileOptions,
+        ProfileArgs,
+        compile,
+    },
+    error::PhoundryError,
+};
+
+/// Directory assertion contracts are compiled from, relative to the project root
+const ASSERTIONS_SRC: &str = "assertions/src";
+
+/// Function every assertion inherits from the credible-std `Assertion` base contract
+const TRIGGERS_FUNCTION: &str = "triggers";
+
+/// Command-line arguments for linting assertion contracts.
+#[derive(Debug, Default, Parser)]
+#[clap(about = "Check assertion contracts for common authoring mistakes")]
+pub struct LintArgs {
+    /// Root directory of the project
+    #[clap(
+        long,
+        value_hint = ValueHint::DirPath,
+        help = "Root directory of the project"
+    )]
+    pub root: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub profile_args: ProfileArgs,
+}
+
+/// Kind of problem reported by `pcl lint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintKind {
+    /// The contract has no `triggers()` function, so it does not extend `Assertion`
+    MissingTriggers,
+    /// The contract registers triggers but exposes no assertion functions
+    NoAssertionFunctions,
+    /// A constructor parameter cannot be passed as a plain command-line argument
+    UnusualConstructorArg,
+}
+
+/// A single lint warning for an assertion contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintWarning {
+    /// Contract name
+    pub contract: String,
+    /// Source file the contract was compiled from
+    pub source: PathBuf,
+    /// Kind of problem
+    pub lint: LintKind,
+    /// Human-readable description
+    pub message: String,
+}
+
+impl LintArgs {
+    /// Compiles the project and lints every assertion contract.
+    ///
+    /// Warnings are reported but do not fail the command.
+    pub fn run(&self, cli_args: &CliArgs) -> Result<(), Box<PhoundryError>> {
+        foundry_cli::utils::load_dotenv();
+
+        let build_opts = BuildOpts {
+            project_paths: ProjectPathOpts {
+                root: self.root.clone(),
+                contracts: Some(PathBuf::from(ASSERTIONS_SRC)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let output = compile(
+            build_opts,
+            CompileOptions {
+                verbose: cli_args.verbose(),
+                profile: self.profile_args.profile.clone(),
+                locked: self.profile_args.locked,
+                ..Default::default()
+            },
+        )?;
+
+        let root = match &self.root {
+            Some(root) => root.clone(),
+            None => {
+                find_project_root(None)
+                    .map_err(|_| PhoundryError::DirectoryNotFound(PathBuf::from(".")))?
+            }
+        };
+        let (checked, warnings) = lint_output(&output, &root);
+
+        if cli_args.json_output() {
+            let report = serde_json::json!({
+                "assertions": checked,
+                "warnings": warnings,
+            });
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&report).map_err(|_| {
+                    PhoundryError::InvalidForgeOutput("Failed to serialize lint report")
+                })?
+            );
+        } else if warnings.is_empty() {
+            println!(
+                "✅ No lint warnings in {checked} assertion contract{}",
+                if checked == 1 { "" } else { "s" }
+            );
+        } else {
+            for warning in &warnings {
+                println!(
+                    "⚠️  {} ({}): {}",
+                    warning.contract,
+                    warning.source.display(),
+                    warning.message
+                );
+            }
+            println!(
+                "\n{} warning{} in {checked} assertion contract{}",
+                warnings.len(),
+                if warnings.len() == 1 { "" } else { "s" },
+                if checked == 1 { "" } else { "s" }
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Lints every deployable contract compiled from the assertions directory.
+///
+/// Returns the number of contracts checked and the warnings found, sorted by
+/// contract name.
+pub fn lint_output(output: &ProjectCompileOutput, root: &Path) -> (usize, Vec<LintWarning>) {
+    let mut checked = 0;
+    let mut warnings = Vec::new();
+    for (id, artifact) in output.artifact_ids() {
+        // Interfaces and abstract contracts have no bytecode and are never submitted
+        let deployable = artifact.get_bytecode_bytes().is_some_and(|b| !b.is_empty());
+        if !deployable || !is_assertion_source(&id.source, root) {
+            continue;
+        }
+        let Some(abi) = artifact.abi.as_ref() else {
+            continue;
+        };
+        checked += 1;
+        warnings.extend(lint_abi(abi).into_iter().map(|(lint, message)| {
+            LintWarning {
+                contract: id.name.clone(),
+                source: id.source.clone(),
+                lint,
+                message,
+            }
+        }));
+    }
+    warnings.sort_by(|a, b| a.contract.cmp(&b.contract));
+    (checked, warnings)
+}
+
+/// Checks a single assertion ABI.
+pub fn lint_abi(abi: &JsonAbi) -> Vec<(LintKind, String)> {
+    let mut warnings = Vec::new();
+
+    if abi.function(TRIGGERS_FUNCTION).is_none() {
+        warnings.push((
+            LintKind::MissingTriggers,
+            "no `triggers()` function; assertions must extend `Assertion`".to_string(),
+        ));
+    } else if abi.functions().all(|f| f.name == TRIGGERS_FUNCTION) {
+        warnings.push((
+            LintKind::NoAssertionFunctions,
+            "no assertion functions besides `triggers()`".to_string(),
+        ));
+    }
+
+    if let Some(constructor) = &abi.constructor {
+        for (index, param) in constructor.inputs.iter().enumerate() {
+            let ty = param.selector_type();
+            if !is_cli_friendly_type(&ty) {
+                warnings.push((
+                    LintKind::UnusualConstructorArg,
+                    format!(
+                        "constructor argument {index} has type '{ty}', which cannot easily be written as a string argument"
+                    ),
+                ));
+            }
+        }
+    }
+
+    warnings
+}
+
+/// Elementary types and one-dimensional arrays of them.
+fn is_cli_friendly_type(ty: &str) -> bool {
+    let element = ty.strip_suffix(']').map_or(ty, |rest| {
+        rest.rsplit_once('[').map_or(rest, |(element, _)| element)
+    });
+    !element.contains(['(', '['])
+}
+
+/// Whether a source path lies in the assertions directory, relative or absolute.
+fn is_assertion_source(source: &Path, root: &Path) -> bool {
+    let relative = source
+        .strip_prefix(root)
+        .or_else(|_| {
+            let root = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+            source.strip_prefix(root).map(Path::to_path_buf)
+        })
+        .unwrap_or_else(|_| source.to_path_buf());
+    relative.starts_with(ASSERTIONS_SRC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abi(json: &str) -> JsonAbi {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_lint_abi_accepts_assertion() {
+        let abi = abi(r#"[
+                {"type":"function","name":"triggers","inputs":[],"outputs":[],"stateMutability":"view"},
+                {"type":"function","name":"assertionOwner","inputs":[],"outputs":[],"stateMutability":"nonpayable"},
+                {"type":"constructor","inputs":[{"name":"a","type":"address"},{"name":"b","type":"uint256[]"}],"stateMutability":"nonpayable"}
+            ]"#);
+        assert!(lint_abi(&abi).is_empty());
+    }
+
+    #[test]
+    fn test_lint_abi_reports_problems() {
+        let no_triggers = abi(
+            r#"[{"type":"function","name":"check","inputs":[],"outputs":[],"stateMutability":"view"}]"#,
+        );
+        let kinds: Vec<_> = lint_abi(&no_triggers).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(kinds, vec![LintKind::MissingTriggers]);
+
+        let triggers_only = abi(r#"[
+                {"type":"function","name":"triggers","inputs":[],"outputs":[],"stateMutability":"view"},
+                {"type":"constructor","inputs":[{"name":"s","type":"tuple","components":[{"name":"x","type":"uint256"}]},{"name":"m","type":"uint256[][]"}],"stateMutability":"nonpayable"}
+            ]"#);
+        let kinds: Vec<_> = lint_abi(&triggers_only)
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LintKind::NoAssertionFunctions,
+                LintKind::UnusualConstructorArg,
+                LintKind::UnusualConstructorArg,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_assertion_source() {
+        let root = Path::new("/project");
+        assert!(is_assertion_source(
+            Path::new("/project/assertions/src/A.a.sol"),
+            root
+        ));
+        assert!(is_assertion_source(
+            Path::new("assertions/src/A.a.sol"),
+            root
+        ));
+        assert!(!is_assertion_source(
+            Path::new("/project/lib/credible-std/src/Assertion.sol"),
+            root
+        ));
+    }
+}
@@ -6,7 +6,12 @@ use forge::{
         ForgeSubcommand,
     },
 };
-
+use foundry_cli::utils::LoadConfig;
+use foundry_common::shell::{
+    OutputFormat,
+    Shell,
+};
+use std::path::Path;
 use tokio::task::spawn_blocking;
 
 use crate::error::PhoundryError;
@@ -23,11 +28,41 @@ pub struct PhorgeTest {
 impl PhorgeTest {
     /// Runs the test command in a separate blocking task.
     /// This prevents blocking the current runtime while executing the forge command.
-    pub async fn run(self) -> Result<(), Box<PhoundryError>> {
+    ///
+    /// With `json`, forge's JSON reporter writes the per-test results to stdout and
+    /// failing tests are returned as [`PhoundryError::TestsFailed`] instead of
+    /// forge exiting the process.
+    pub async fn run(self, json: bool) -> Result<(), Box<PhoundryError>> {
         // Extract the Send-safe parts of the test args
         let test_args = self.test_args;
         let global_opts = test_args.global.clone();
         global_opts.init()?;
+        if json {
+            use_json_shell();
+        }
+        // `pcl test --json` reaches forge directly, `pcl --json test` through `json`
+        let json = json || foundry_common::shell::is_json();
+
+        // Fail early with a clear message instead of an opaque forge error
+        let config = test_args.load_config()?;
+        let test_dir = config.root.join(&config.test);
+        if !contains_solidity_files(&test_dir) {
+            return Err(Box::new(PhoundryError::NoTestFilesFound(test_dir)));
+        }
+
+        if json {
+            return spawn_blocking(move || {
+                let outcome = global_opts.block_on(test_args.run())?;
+                let failed = outcome.failures().count();
+                if failed > 0 && !outcome.allow_failure {
+                    return Err(Box::new(PhoundryError::TestsFailed(failed)));
+                }
+                Ok(())
+            })
+            .await
+            .map_err(|e| Box::new(PhoundryError::ForgeCommandFailed(e.into())))?;
+        }
+
         // Spawn the blocking operation in a separate task
         spawn_blocking(move || {
             // Reconstruct the Forge struct inside the closure
@@ -42,3 +77,47 @@ impl PhorgeTest {
         Ok(())
     }
 }
+
+/// Switches forge's global shell to JSON output, keeping the other display options.
+fn use_json_shell() {
+    let shell = Shell::get();
+    let (mode, color, verbosity) = (shell.output_mode(), shell.color_choice(), shell.verbosity());
+    // The shell is behind a global lock that `set` takes again
+    drop(shell);
+    Shell::new_with(OutputFormat::Json, mode, color, verbosity).set();
+}
+
+/// Returns true if `dir` contains a Solidity file, searching subdirectories.
+fn contains_solidity_files(dir: &Path) -> bool {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries.flatten().any(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            contains_solidity_files(&path)
+        } else {
+            path.extension().is_some_and(|ext| ext == "sol")
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_contains_solidity_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let test_dir = temp_dir.path().join("test");
+        assert!(!contains_solidity_files(&test_dir));
+
+        std::fs::create_dir_all(test_dir.join("nested")).unwrap();
+        std::fs::write(test_dir.join("README.md"), "").unwrap();
+        assert!(!contains_solidity_files(&test_dir));
+
+        std::fs::write(test_dir.join("nested").join("Counter.t.sol"), "").unwrap();
+        assert!(contains_solidity_files(&test_dir));
+    }
+}